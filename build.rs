@@ -0,0 +1,56 @@
+//! Generates the list of solutions that the main binary runs in-process when the `registry` feature is enabled.
+use std::{env, fs, path::PathBuf};
+
+fn main() {
+    // without any `rerun-if` line, cargo would re-run this script whenever a file of the package changes.
+    println!("cargo:rerun-if-changed=build.rs");
+
+    if env::var_os("CARGO_FEATURE_REGISTRY").is_none() {
+        return;
+    }
+
+    println!("cargo:rerun-if-changed=src/bin");
+
+    let manifest_dir = PathBuf::from(env::var("CARGO_MANIFEST_DIR").unwrap());
+    let out_path = PathBuf::from(env::var("OUT_DIR").unwrap()).join("registry.rs");
+
+    let mut puzzles: Vec<(String, u16, u8)> = fs::read_dir(manifest_dir.join("src").join("bin"))
+        .into_iter()
+        .flatten()
+        .filter_map(Result::ok)
+        .filter_map(|entry| {
+            let name = entry.file_name().to_str()?.strip_suffix(".rs")?.to_string();
+            let (year, day) = name.split_once('-')?;
+            if year.len() != 4 || day.len() != 2 {
+                return None;
+            }
+            Some((name.clone(), year.parse().ok()?, day.parse().ok()?))
+        })
+        .collect();
+
+    puzzles.sort_unstable();
+
+    let mut modules = String::new();
+    let mut solutions = String::new();
+
+    for (name, year, day) in &puzzles {
+        let module = format!("_{}", name.replace('-', "_"));
+        let path = manifest_dir
+            .join("src")
+            .join("bin")
+            .join(format!("{name}.rs"));
+
+        modules.push_str(&format!(
+            "#[path = {path:?}]\n#[allow(warnings, clippy::all, clippy::pedantic)]\nmod {module};\n"
+        ));
+        solutions.push_str(&format!(
+            "    Solution {{ puzzle: Puzzle::new(advent_of_code::year!({year}), advent_of_code::day!({day})), main: {module}::main }},\n"
+        ));
+    }
+
+    let contents = format!(
+        "use advent_of_code::template::{{registry::Solution, Puzzle}};\n\n{modules}\npub static SOLUTIONS: &[Solution] = &[\n{solutions}];\n"
+    );
+
+    fs::write(out_path, contents).unwrap();
+}
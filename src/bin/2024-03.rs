@@ -36,21 +36,7 @@ pub fn part_two(input: &str) -> Option<u32> {
     Some(answer)
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-
-    #[test]
-    fn test_part_one() {
-        let result = part_one(&advent_of_code::template::read_file("examples", DAY));
-        assert_eq!(result, Some(161));
-    }
-
-    #[test]
-    fn test_part_two() {
-        let result = part_two(&advent_of_code::template::read_file_part(
-            "examples", DAY, 2,
-        ));
-        assert_eq!(result, Some(48));
-    }
+advent_of_code::examples! {
+    part_one: ["03.txt" => 161],
+    part_two: ["03-2.txt" => 48],
 }
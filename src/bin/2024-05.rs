@@ -2,7 +2,7 @@ use regex::Regex;
 use std::collections::HashMap;
 use std::ops::Index;
 use std::str::FromStr;
-use crate::Status::{Invalid, Unknown, Valid};
+use self::Status::{Invalid, Unknown, Valid};
 use petgraph::graph::Graph;
 use petgraph::visit::IntoNodeReferences;
 use petgraph::algo::toposort;
@@ -173,13 +173,13 @@ mod tests {
 
     #[test]
     fn test_part_one() {
-        let result = part_one(&advent_of_code::template::read_file("examples", DAY));
+        let result = part_one(&advent_of_code::template::read_file("examples", PUZZLE));
         assert_eq!(result, Some(143));
     }
 
     #[test]
     fn test_part_two() {
-        let result = part_two(&advent_of_code::template::read_file("inputs", DAY));
+        let result = part_two(&advent_of_code::template::read_file("inputs", PUZZLE));
         assert_eq!(result, Some(123));
     }
 }
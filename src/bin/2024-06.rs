@@ -1,7 +1,8 @@
-use crate::Direction::{Down, Left, Right, Up};
+use self::Direction::{Down, Left, Right, Up};
+use advent_of_code::template::Progress;
 use std::collections::{HashMap, HashSet};
 
-advent_of_code::solution!(6);
+advent_of_code::solution!(6, parse);
 
 #[derive(Debug, PartialEq, Hash, Eq, Clone, Copy)]
 struct Coordinates(isize, isize);
@@ -61,7 +62,7 @@ impl Guard {
 struct CycleError;
 
 #[derive(Clone)]
-struct World {
+pub struct World {
     obstacles: Vec<Coordinates>,
     guard: Option<Guard>,
     visited_locations: HashMap<Coordinates, HashSet<Direction>>,
@@ -151,13 +152,12 @@ impl World {
         Ok(())
     }
 
-    fn find_cyclical_obstacles(&mut self) -> Vec<Coordinates> {
+    fn find_cyclical_obstacles(&self) -> Vec<Coordinates> {
         let mut cycle_coords = vec![];
         let mut copy = self.clone();
         copy.run().expect("Unable to run the world");
         let visited_locations = &copy.visited_locations;
-        println!("Checking {} valid path locations.", visited_locations.len());
-        let mut index: f64 = 0.0;
+        let mut progress = Progress::new("Checking path locations", visited_locations.len());
         for coord in visited_locations.keys() {
             let i = coord.0 as isize;
             let j = coord.1 as isize;
@@ -170,8 +170,7 @@ impl World {
             if result.is_err() {
                 cycle_coords.push(coords);
             }
-            index += 1.0;
-            println!("%{}", index / visited_locations.len() as f64 * 100.0);
+            progress.inc();
         }
 
         cycle_coords
@@ -181,30 +180,22 @@ impl World {
         self.visited_locations.len() as u32
     }
 }
-pub fn part_one(input: &str) -> Option<u32> {
-    let mut world = World::new(input);
+pub fn parse(input: &str) -> World {
+    World::new(input)
+}
+
+pub fn part_one(world: &World) -> Option<u32> {
+    let mut world = world.clone();
     world.run().expect("PANIC CYCLE DETECTED");
     Some(world.visited_count())
 }
 
-pub fn part_two(input: &str) -> Option<u32> {
-    let mut world = World::new(input);
+pub fn part_two(world: &World) -> Option<u32> {
     Some(world.find_cyclical_obstacles().len() as u32)
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-
-    #[test]
-    fn test_part_one() {
-        let result = part_one(&advent_of_code::template::read_file("examples", DAY));
-        assert_eq!(result, Some(41));
-    }
-
-    #[test]
-    fn test_part_two() {
-        let result = part_two(&advent_of_code::template::read_file("examples", DAY));
-        assert_eq!(result, Some(6));
-    }
+advent_of_code::examples! {
+    parse: parse,
+    part_one: ["06.txt" => 41],
+    part_two: ["06.txt" => 6],
 }
@@ -1,5 +1,5 @@
 use std::str::FromStr;
-use crate::Operations::{Add, Concact, Multiply};
+use self::Operations::{Add, Concact, Multiply};
 
 advent_of_code::solution!(7);
 
@@ -108,13 +108,13 @@ mod tests {
 
     #[test]
     fn test_part_one() {
-        let result = part_one(&advent_of_code::template::read_file("examples", DAY));
+        let result = part_one(&advent_of_code::template::read_file("examples", PUZZLE));
         assert_eq!(result, Some(3749));
     }
 
     #[test]
     fn test_part_two() {
-        let result = part_two(&advent_of_code::template::read_file("examples", DAY));
+        let result = part_two(&advent_of_code::template::read_file("examples", PUZZLE));
         assert_eq!(result, Some(11387));
     }
 }
@@ -0,0 +1,193 @@
+//! Cycle detection for simulations that repeatedly apply a step function to a state.
+use std::collections::HashMap;
+use std::hash::Hash;
+
+/// A cycle in a sequence of states `x0, x1 = step(x0), x2 = step(x1), ...`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Cycle {
+    /// Index of the first state that is part of the cycle.
+    pub start: usize,
+    /// Number of states in the cycle.
+    pub length: usize,
+}
+
+impl Cycle {
+    /// Maps the index of any state to the index of the equal state within the first pass of the cycle.
+    pub fn reduce(self, index: usize) -> usize {
+        if index < self.start {
+            index
+        } else {
+            self.start + (index - self.start) % self.length
+        }
+    }
+}
+
+/// Floyd's tortoise and hare algorithm. Uses constant memory, but calls `step` about three times per state.
+/// The sequence must be cyclic, otherwise this function does not return.
+pub fn floyd<S: Clone + PartialEq>(initial: S, mut step: impl FnMut(&S) -> S) -> Cycle {
+    let mut tortoise = step(&initial);
+    let mut hare = step(&tortoise);
+    while tortoise != hare {
+        tortoise = step(&tortoise);
+        hare = step(&hare);
+        hare = step(&hare);
+    }
+
+    let mut start = 0;
+    tortoise = initial;
+    while tortoise != hare {
+        tortoise = step(&tortoise);
+        hare = step(&hare);
+        start += 1;
+    }
+
+    let mut length = 1;
+    hare = step(&tortoise);
+    while tortoise != hare {
+        hare = step(&hare);
+        length += 1;
+    }
+
+    Cycle { start, length }
+}
+
+/// Brent's algorithm. Uses constant memory and calls `step` fewer times than [`floyd`].
+/// The sequence must be cyclic, otherwise this function does not return.
+pub fn brent<S: Clone + PartialEq>(initial: S, mut step: impl FnMut(&S) -> S) -> Cycle {
+    let mut power = 1;
+    let mut length = 1;
+    let mut tortoise = initial.clone();
+    let mut hare = step(&initial);
+
+    while tortoise != hare {
+        if power == length {
+            tortoise = hare.clone();
+            power *= 2;
+            length = 0;
+        }
+        hare = step(&hare);
+        length += 1;
+    }
+
+    tortoise = initial.clone();
+    hare = initial;
+    for _ in 0..length {
+        hare = step(&hare);
+    }
+
+    let mut start = 0;
+    while tortoise != hare {
+        tortoise = step(&tortoise);
+        hare = step(&hare);
+        start += 1;
+    }
+
+    Cycle { start, length }
+}
+
+/// Remembers every state and stops at the first repeated one. Calls `step` once per state.
+/// The sequence must be cyclic, otherwise this function does not return.
+pub fn hashed<S: Clone + Eq + Hash>(initial: S, mut step: impl FnMut(&S) -> S) -> Cycle {
+    hashed_until(initial, |state| Some(step(state))).unwrap()
+}
+
+/// Like [`hashed`], for simulations that can end: returns [`None`] once `step` returns [`None`],
+/// e.g. when a guard walks off the grid instead of walking in a loop.
+pub fn hashed_until<S: Clone + Eq + Hash>(
+    initial: S,
+    mut step: impl FnMut(&S) -> Option<S>,
+) -> Option<Cycle> {
+    let mut seen: HashMap<S, usize> = HashMap::new();
+    let mut state = initial;
+
+    for index in 0.. {
+        if let Some(start) = seen.insert(state.clone(), index) {
+            return Some(Cycle {
+                start,
+                length: index - start,
+            });
+        }
+        state = step(&state)?;
+    }
+
+    unreachable!()
+}
+
+/// Returns the state after `n` steps, e.g. for puzzles that ask about step one billion.
+/// Steps are simulated until a state repeats, the remaining steps are skipped using the detected cycle.
+pub fn simulate<S: Clone + Eq + Hash>(initial: S, n: usize, mut step: impl FnMut(&S) -> S) -> S {
+    let mut seen: HashMap<S, usize> = HashMap::new();
+    let mut states = vec![];
+    let mut state = initial;
+
+    for index in 0..n {
+        if let Some(start) = seen.insert(state.clone(), index) {
+            let cycle = Cycle {
+                start,
+                length: index - start,
+            };
+            return states.swap_remove(cycle.reduce(n));
+        }
+        states.push(state.clone());
+        state = step(&state);
+    }
+
+    state
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::{brent, floyd, hashed, hashed_until, simulate, Cycle};
+
+    /// `0, 1, 2, 3, 4, 5, 2, 3, 4, 5, ...`
+    fn step(x: &u32) -> u32 {
+        if *x == 5 {
+            2
+        } else {
+            x + 1
+        }
+    }
+
+    const CYCLE: Cycle = Cycle {
+        start: 2,
+        length: 4,
+    };
+
+    #[test]
+    fn detects_cycles() {
+        assert_eq!(floyd(0, step), CYCLE);
+        assert_eq!(brent(0, step), CYCLE);
+        assert_eq!(hashed(0, step), CYCLE);
+        assert_eq!(
+            hashed(7, |x| (x * x + 1) % 255),
+            floyd(7, |x| (x * x + 1) % 255)
+        );
+        assert_eq!(
+            brent(3, |x: &u32| *x),
+            Cycle {
+                start: 0,
+                length: 1
+            }
+        );
+    }
+
+    #[test]
+    fn detects_ending_simulations() {
+        assert_eq!(hashed_until(0, |x| (*x < 10).then_some(x + 1)), None);
+        assert_eq!(hashed_until(0, |x| Some(step(x))), Some(CYCLE));
+    }
+
+    #[test]
+    fn skips_ahead_with_cycles() {
+        assert_eq!(CYCLE.reduce(1), 1);
+        assert_eq!(CYCLE.reduce(6), 2);
+        assert_eq!(simulate(0, 0, step), 0);
+        assert_eq!(simulate(0, 4, step), 4);
+        assert_eq!(
+            simulate(0, 1_000_000_000, step),
+            2 + (1_000_000_000 - 2) % 4
+        );
+    }
+}
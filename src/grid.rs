@@ -0,0 +1,576 @@
+//! Two-dimensional grids, as used by many puzzles.
+use std::fmt::Display;
+use std::ops::{Add, AddAssign, Index, IndexMut, Mul, Neg, Sub};
+use std::str::FromStr;
+
+use crate::template::ParseError;
+
+/// A position on a grid. `x` grows to the right and `y` grows downwards.
+/// Coordinates are signed, so that positions next to the grid can be represented.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub struct Point {
+    pub x: isize,
+    pub y: isize,
+}
+
+impl Point {
+    pub const fn new(x: isize, y: isize) -> Self {
+        Self { x, y }
+    }
+
+    /// Returns the manhattan distance between two points.
+    pub fn manhattan(self, other: Point) -> usize {
+        self.x.abs_diff(other.x) + self.y.abs_diff(other.y)
+    }
+}
+
+impl Display for Point {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{},{}", self.x, self.y)
+    }
+}
+
+/// The offset between two points.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub struct Vector {
+    pub x: isize,
+    pub y: isize,
+}
+
+impl Vector {
+    pub const fn new(x: isize, y: isize) -> Self {
+        Self { x, y }
+    }
+
+    /// Rotates the vector by 90 degrees clockwise.
+    pub const fn turn_right(self) -> Self {
+        Self::new(-self.y, self.x)
+    }
+
+    /// Rotates the vector by 90 degrees counter-clockwise.
+    pub const fn turn_left(self) -> Self {
+        Self::new(self.y, -self.x)
+    }
+}
+
+impl Add<Vector> for Point {
+    type Output = Point;
+
+    fn add(self, rhs: Vector) -> Point {
+        Point::new(self.x + rhs.x, self.y + rhs.y)
+    }
+}
+
+impl AddAssign<Vector> for Point {
+    fn add_assign(&mut self, rhs: Vector) {
+        *self = *self + rhs;
+    }
+}
+
+impl Sub<Vector> for Point {
+    type Output = Point;
+
+    fn sub(self, rhs: Vector) -> Point {
+        self + -rhs
+    }
+}
+
+impl Sub<Point> for Point {
+    type Output = Vector;
+
+    fn sub(self, rhs: Point) -> Vector {
+        Vector::new(self.x - rhs.x, self.y - rhs.y)
+    }
+}
+
+impl Add<Vector> for Vector {
+    type Output = Vector;
+
+    fn add(self, rhs: Vector) -> Vector {
+        Vector::new(self.x + rhs.x, self.y + rhs.y)
+    }
+}
+
+impl Mul<isize> for Vector {
+    type Output = Vector;
+
+    fn mul(self, rhs: isize) -> Vector {
+        Vector::new(self.x * rhs, self.y * rhs)
+    }
+}
+
+impl Neg for Vector {
+    type Output = Vector;
+
+    fn neg(self) -> Vector {
+        Vector::new(-self.x, -self.y)
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+/// One of the four orthogonal directions.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Direction {
+    Up,
+    Right,
+    Down,
+    Left,
+}
+
+impl Direction {
+    /// All directions in clockwise order, starting with [`Direction::Up`].
+    pub const ALL: [Direction; 4] = [
+        Direction::Up,
+        Direction::Right,
+        Direction::Down,
+        Direction::Left,
+    ];
+
+    pub const fn vector(self) -> Vector {
+        match self {
+            Direction::Up => Vector::new(0, -1),
+            Direction::Right => Vector::new(1, 0),
+            Direction::Down => Vector::new(0, 1),
+            Direction::Left => Vector::new(-1, 0),
+        }
+    }
+
+    pub const fn turn_right(self) -> Self {
+        match self {
+            Direction::Up => Direction::Right,
+            Direction::Right => Direction::Down,
+            Direction::Down => Direction::Left,
+            Direction::Left => Direction::Up,
+        }
+    }
+
+    pub const fn turn_left(self) -> Self {
+        match self {
+            Direction::Up => Direction::Left,
+            Direction::Right => Direction::Up,
+            Direction::Down => Direction::Right,
+            Direction::Left => Direction::Down,
+        }
+    }
+
+    pub const fn reverse(self) -> Self {
+        self.turn_right().turn_right()
+    }
+
+    /// Parses arrows (`^>v<`) and the letters `U`, `R`, `D` and `L`.
+    pub const fn from_char(c: char) -> Option<Self> {
+        match c {
+            '^' | 'U' => Some(Direction::Up),
+            '>' | 'R' => Some(Direction::Right),
+            'v' | 'D' => Some(Direction::Down),
+            '<' | 'L' => Some(Direction::Left),
+            _ => None,
+        }
+    }
+}
+
+/// One of the eight directions including diagonals, starting with north and going clockwise.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Direction8 {
+    N,
+    NE,
+    E,
+    SE,
+    S,
+    SW,
+    W,
+    NW,
+}
+
+impl Direction8 {
+    /// All directions in clockwise order, starting with [`Direction8::N`].
+    pub const ALL: [Direction8; 8] = [
+        Direction8::N,
+        Direction8::NE,
+        Direction8::E,
+        Direction8::SE,
+        Direction8::S,
+        Direction8::SW,
+        Direction8::W,
+        Direction8::NW,
+    ];
+
+    pub const fn vector(self) -> Vector {
+        match self {
+            Direction8::N => Vector::new(0, -1),
+            Direction8::NE => Vector::new(1, -1),
+            Direction8::E => Vector::new(1, 0),
+            Direction8::SE => Vector::new(1, 1),
+            Direction8::S => Vector::new(0, 1),
+            Direction8::SW => Vector::new(-1, 1),
+            Direction8::W => Vector::new(-1, 0),
+            Direction8::NW => Vector::new(-1, -1),
+        }
+    }
+
+    /// Rotates by 45 degrees clockwise.
+    pub const fn turn_right(self) -> Self {
+        Self::ALL[(self as usize + 1) % 8]
+    }
+
+    /// Rotates by 45 degrees counter-clockwise.
+    pub const fn turn_left(self) -> Self {
+        Self::ALL[(self as usize + 7) % 8]
+    }
+
+    pub const fn reverse(self) -> Self {
+        Self::ALL[(self as usize + 4) % 8]
+    }
+
+    pub const fn is_diagonal(self) -> bool {
+        self as usize % 2 == 1
+    }
+}
+
+impl From<Direction> for Direction8 {
+    fn from(value: Direction) -> Self {
+        match value {
+            Direction::Up => Direction8::N,
+            Direction::Right => Direction8::E,
+            Direction::Down => Direction8::S,
+            Direction::Left => Direction8::W,
+        }
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+/// A rectangular grid of cells, stored row by row.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Grid<T> {
+    width: usize,
+    height: usize,
+    cells: Vec<T>,
+}
+
+impl<T> Grid<T> {
+    /// Creates a grid of `width` x `height` cells with the value `fill`.
+    pub fn new(width: usize, height: usize, fill: T) -> Self
+    where
+        T: Clone,
+    {
+        Self {
+            width,
+            height,
+            cells: vec![fill; width * height],
+        }
+    }
+
+    /// Creates a grid from cells in row order. Panics if the cells do not fill whole rows.
+    pub fn from_vec(width: usize, cells: Vec<T>) -> Self {
+        assert!(
+            width > 0 && cells.len().is_multiple_of(width),
+            "cells do not fill rows of width {width}"
+        );
+        Self {
+            width,
+            height: cells.len() / width,
+            cells,
+        }
+    }
+
+    /// Parses a grid with one character per cell, e.g. `|c| c == '#'`.
+    /// Blank lines before and after the grid are skipped.
+    /// Fails if the lines differ in length or `parse_cell` fails.
+    pub fn parse<E: Display>(
+        input: &str,
+        mut parse_cell: impl FnMut(char) -> Result<T, E>,
+    ) -> Result<Self, ParseError> {
+        let mut width = None;
+        let mut cells = vec![];
+        let mut height = 0;
+
+        let lines: Vec<&str> = input
+            .lines()
+            .map(|line| line.trim_end_matches('\r'))
+            .collect();
+        let end = lines
+            .iter()
+            .rposition(|line| !line.is_empty())
+            .map_or(0, |y| y + 1);
+
+        for (y, line) in lines[..end].iter().enumerate() {
+            if line.is_empty() && width.is_none() {
+                continue;
+            }
+
+            let line_width = line.chars().count();
+            let expected = *width.get_or_insert(line_width);
+            if line_width != expected {
+                return Err(ParseError::at(
+                    input,
+                    y + 1,
+                    line_width.min(expected) + 1,
+                    format!("expected a row of {expected} cells, found {line_width}"),
+                ));
+            }
+
+            for (x, c) in line.chars().enumerate() {
+                let cell = parse_cell(c)
+                    .map_err(|e| ParseError::at(input, y + 1, x + 1, e.to_string()))?;
+                cells.push(cell);
+            }
+
+            height += 1;
+        }
+
+        Ok(Self {
+            width: width.unwrap_or(0),
+            height,
+            cells,
+        })
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// Returns whether `point` lies on the grid.
+    pub fn contains(&self, point: Point) -> bool {
+        self.index_of(point).is_some()
+    }
+
+    pub fn get(&self, point: Point) -> Option<&T> {
+        self.index_of(point).map(|i| &self.cells[i])
+    }
+
+    pub fn get_mut(&mut self, point: Point) -> Option<&mut T> {
+        self.index_of(point).map(|i| &mut self.cells[i])
+    }
+
+    /// Returns all points of the grid in row order.
+    pub fn points(&self) -> impl Iterator<Item = Point> {
+        let width = self.width;
+        (0..self.cells.len()).map(move |i| Self::point_at(width, i))
+    }
+
+    /// Returns all cells together with their points in row order.
+    pub fn iter(&self) -> impl Iterator<Item = (Point, &T)> {
+        let width = self.width;
+        self.cells
+            .iter()
+            .enumerate()
+            .map(move |(i, cell)| (Self::point_at(width, i), cell))
+    }
+
+    /// Returns the orthogonal neighbors of `point` that lie on the grid.
+    pub fn neighbors4(&self, point: Point) -> impl Iterator<Item = Point> + '_ {
+        Direction::ALL
+            .into_iter()
+            .map(move |d| point + d.vector())
+            .filter(|p| self.contains(*p))
+    }
+
+    /// Returns the orthogonal and diagonal neighbors of `point` that lie on the grid.
+    pub fn neighbors8(&self, point: Point) -> impl Iterator<Item = Point> + '_ {
+        Direction8::ALL
+            .into_iter()
+            .map(move |d| point + d.vector())
+            .filter(|p| self.contains(*p))
+    }
+
+    /// Returns the cells of row `y`.
+    pub fn row(&self, y: usize) -> &[T] {
+        &self.cells[y * self.width..(y + 1) * self.width]
+    }
+
+    /// Returns the cells of column `x`, from top to bottom.
+    pub fn column(&self, x: usize) -> impl Iterator<Item = &T> {
+        assert!(x < self.width, "column {x} is out of bounds");
+        self.cells.iter().skip(x).step_by(self.width)
+    }
+
+    /// Walks from `start` in steps of `step` until the walk leaves the grid, `start` included.
+    /// With a diagonal step, this slices a diagonal of the grid.
+    pub fn ray(&self, start: Point, step: Vector) -> impl Iterator<Item = (Point, &T)> {
+        let mut point = start;
+        std::iter::from_fn(move || {
+            let cell = self.get(point)?;
+            let current = point;
+            point += step;
+            Some((current, cell))
+        })
+    }
+
+    /// Returns the points of all cells equal to `value`, in row order.
+    pub fn find_all<'a>(&'a self, value: &'a T) -> impl Iterator<Item = Point> + 'a
+    where
+        T: PartialEq,
+    {
+        self.iter()
+            .filter(move |(_, cell)| *cell == value)
+            .map(|(point, _)| point)
+    }
+
+    /// Returns the point of the first cell equal to `value`.
+    pub fn find(&self, value: &T) -> Option<Point>
+    where
+        T: PartialEq,
+    {
+        self.find_all(value).next()
+    }
+
+    /// Creates a grid of the same size by mapping every cell.
+    pub fn map<U>(&self, f: impl FnMut(&T) -> U) -> Grid<U> {
+        Grid {
+            width: self.width,
+            height: self.height,
+            cells: self.cells.iter().map(f).collect(),
+        }
+    }
+
+    fn index_of(&self, point: Point) -> Option<usize> {
+        let x = usize::try_from(point.x).ok()?;
+        let y = usize::try_from(point.y).ok()?;
+        (x < self.width && y < self.height).then_some(y * self.width + x)
+    }
+
+    #[allow(clippy::cast_possible_wrap)]
+    fn point_at(width: usize, index: usize) -> Point {
+        Point::new((index % width) as isize, (index / width) as isize)
+    }
+}
+
+impl<T> Index<Point> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, point: Point) -> &T {
+        self.get(point)
+            .unwrap_or_else(|| panic!("point {point} is not on the grid"))
+    }
+}
+
+impl<T> IndexMut<Point> for Grid<T> {
+    fn index_mut(&mut self, point: Point) -> &mut T {
+        self.get_mut(point)
+            .unwrap_or_else(|| panic!("point {point} is not on the grid"))
+    }
+}
+
+impl FromStr for Grid<char> {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse(s, Ok::<_, ParseError>)
+    }
+}
+
+/// Displays the grid with one line per row, e.g. to print it back as the puzzle shows it.
+impl<T: Display> Display for Grid<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for y in 0..self.height {
+            if y > 0 {
+                writeln!(f)?;
+            }
+            for cell in self.row(y) {
+                write!(f, "{cell}")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::{Direction, Direction8, Grid, Point, Vector};
+
+    const INPUT: &str = "MMS\nAXA\nMAS\n";
+
+    #[test]
+    fn parses_and_displays_grids() {
+        let grid: Grid<char> = INPUT.parse().unwrap();
+        assert_eq!((grid.width(), grid.height()), (3, 3));
+        assert_eq!(grid[Point::new(1, 1)], 'X');
+        assert_eq!(grid.to_string(), INPUT.trim_end());
+
+        let error = "..\n...\n".parse::<Grid<char>>().unwrap_err();
+        assert_eq!((error.line, error.column), (2, 3));
+
+        let error = Grid::parse("#.\n.x", |c| match c {
+            '#' => Ok(true),
+            '.' => Ok(false),
+            _ => Err("unexpected cell"),
+        })
+        .unwrap_err();
+        assert_eq!((error.line, error.column), (2, 2));
+    }
+
+    #[test]
+    fn skips_surrounding_blank_lines() {
+        let grid: Grid<char> = "\n..#\n#..\n\n\n".parse().unwrap();
+        assert_eq!((grid.width(), grid.height()), (3, 2));
+        assert_eq!(grid.to_string(), "..#\n#..");
+
+        let error = "..#\n\n#..\n".parse::<Grid<char>>().unwrap_err();
+        assert_eq!((error.line, error.column), (2, 1));
+    }
+
+    #[test]
+    fn checks_bounds() {
+        let grid: Grid<char> = INPUT.parse().unwrap();
+        assert!(grid.contains(Point::new(2, 2)));
+        assert!(!grid.contains(Point::new(-1, 0)));
+        assert!(!grid.contains(Point::new(0, 3)));
+        assert_eq!(grid.get(Point::new(3, 0)), None);
+        assert_eq!(grid.neighbors4(Point::new(0, 0)).count(), 2);
+        assert_eq!(grid.neighbors8(Point::new(0, 0)).count(), 3);
+        assert_eq!(grid.neighbors8(Point::new(1, 1)).count(), 8);
+    }
+
+    #[test]
+    fn slices_grids() {
+        let grid: Grid<char> = INPUT.parse().unwrap();
+        assert_eq!(grid.row(1), &['A', 'X', 'A']);
+        assert_eq!(grid.column(2).collect::<String>(), "SAS");
+
+        let diagonal: String = grid
+            .ray(Point::new(0, 0), Direction8::SE.vector())
+            .map(|(_, c)| c)
+            .collect();
+        assert_eq!(diagonal, "MXS");
+
+        assert_eq!(
+            grid.find_all(&'M').collect::<Vec<_>>(),
+            vec![Point::new(0, 0), Point::new(1, 0), Point::new(0, 2)]
+        );
+        assert_eq!(grid.find(&'X'), Some(Point::new(1, 1)));
+        assert_eq!(grid.find(&'Z'), None);
+    }
+
+    #[test]
+    fn rotates_directions() {
+        assert_eq!(Direction::Up.turn_right(), Direction::Right);
+        assert_eq!(Direction::Up.turn_left(), Direction::Left);
+        assert_eq!(Direction::Left.reverse(), Direction::Right);
+        assert_eq!(Direction::from_char('v'), Some(Direction::Down));
+        assert_eq!(Direction8::NW.turn_right(), Direction8::N);
+        assert_eq!(Direction8::N.turn_left(), Direction8::NW);
+        assert_eq!(Direction8::NE.reverse(), Direction8::SW);
+        assert_eq!(
+            Direction::Up.vector().turn_right(),
+            Direction::Right.vector()
+        );
+    }
+
+    #[test]
+    fn does_point_arithmetic() {
+        let a = Point::new(1, 2);
+        let b = Point::new(4, 0);
+        assert_eq!(b - a, Vector::new(3, -2));
+        assert_eq!(a + (b - a) * 2, Point::new(7, -2));
+        assert_eq!(b - (b - a), a);
+        assert_eq!(a.manhattan(b), 5);
+    }
+}
@@ -1,3 +1,8 @@
+pub mod cycle;
+pub mod grid;
+pub mod parse;
+pub mod search;
 pub mod template;
+pub mod viz;
 
 // Use this file to add helper functions and additional modules.
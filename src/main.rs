@@ -1,78 +1,174 @@
-use advent_of_code::template::commands::{all, download, read, scaffold, solve, time};
+use advent_of_code::template::commands::{
+    all, check_answers, download, examples, read, scaffold, solve, time, watch,
+};
 use args::{parse, AppArguments};
 
 #[cfg(feature = "today")]
-use advent_of_code::template::Day;
+use advent_of_code::template::{Day, Puzzle, Year};
 #[cfg(feature = "today")]
 use std::process;
 
+#[cfg(all(feature = "registry", feature = "dhat-heap"))]
+compile_error!("the `registry` and `dhat-heap` features can not be combined, as every solution defines a global allocator.");
+
+#[cfg(all(feature = "registry", feature = "memory"))]
+#[global_allocator]
+static ALLOC: advent_of_code::template::memory::CountingAlloc =
+    advent_of_code::template::memory::CountingAlloc;
+
+/// Solutions compiled into this binary, see `build.rs`.
+#[cfg(feature = "registry")]
+mod registry {
+    include!(concat!(env!("OUT_DIR"), "/registry.rs"));
+}
+
 mod args {
-    use advent_of_code::template::Day;
+    use advent_of_code::template::{Day, Puzzle, Year};
+    use advent_of_code::viz::Target;
     use std::process;
+    use std::time::Duration;
 
     pub enum AppArguments {
         Download {
-            day: Day,
+            puzzle: Puzzle,
         },
         Read {
-            day: Day,
+            puzzle: Puzzle,
+        },
+        Examples {
+            puzzle: Puzzle,
+            pick: bool,
+            overwrite: bool,
         },
         Scaffold {
-            day: Day,
+            puzzle: Puzzle,
             download: bool,
             overwrite: bool,
         },
         Solve {
-            day: Day,
+            puzzle: Puzzle,
             release: bool,
             dhat: bool,
             submit: Option<u8>,
+            viz: Option<Target>,
+            memory: bool,
         },
         All {
+            year: Year,
+            release: bool,
+            jobs: usize,
+        },
+        CheckAnswers {
+            year: Year,
             release: bool,
+            jobs: usize,
         },
         Time {
+            year: Year,
             all: bool,
             day: Option<Day>,
             store: bool,
+            budget: Option<Duration>,
+            threshold: Option<f64>,
+            fail_on_regression: bool,
+            jobs: usize,
+        },
+        History {
+            puzzle: Puzzle,
+        },
+        Watch {
+            puzzle: Puzzle,
+            release: bool,
         },
         #[cfg(feature = "today")]
         Today,
     }
 
+    /// Combines a year and a day, making sure that the day is part of the year's event.
+    fn puzzle(year: Year, day: Day) -> Result<Puzzle, String> {
+        Puzzle::checked(year, day)
+            .ok_or_else(|| format!("the {year} event only has {} days.", year.days()))
+    }
+
     pub fn parse() -> Result<AppArguments, Box<dyn std::error::Error>> {
         let mut args = pico_args::Arguments::from_env();
+        let subcommand = args.subcommand()?;
+
+        // the year defaults to `AOC_YEAR`, which is set in `.cargo/config.toml`.
+        let year_arg: Option<Year> = args.opt_value_from_str("--year")?;
+        let year = || {
+            year_arg.or_else(Year::from_env).ok_or(
+                "no year specified. Pass `--year <year>` or set `AOC_YEAR` in `.cargo/config.toml`.",
+            )
+        };
+
+        // solutions run one after another unless `--jobs <n>` is passed.
+        let jobs: usize = args.opt_value_from_str("--jobs")?.unwrap_or(1);
 
-        let app_args = match args.subcommand()?.as_deref() {
+        let app_args = match subcommand.as_deref() {
             Some("all") => AppArguments::All {
+                year: year()?,
                 release: args.contains("--release"),
+                jobs,
+            },
+            Some("check-answers") => AppArguments::CheckAnswers {
+                year: year()?,
+                release: args.contains("--release"),
+                jobs,
+            },
+            Some("time") if args.contains("--history") => AppArguments::History {
+                puzzle: puzzle(year()?, args.free_from_str()?)?,
             },
             Some("time") => {
                 let all = args.contains("--all");
                 let store = args.contains("--store");
+                let budget = args
+                    .opt_value_from_str::<_, u64>("--budget")?
+                    .map(Duration::from_millis);
+                let threshold = args.opt_value_from_str("--threshold")?;
+                let fail_on_regression = args.contains("--fail-on-regression");
 
                 AppArguments::Time {
+                    year: year()?,
                     all,
                     day: args.opt_free_from_str()?,
                     store,
+                    budget,
+                    threshold,
+                    fail_on_regression,
+                    jobs,
                 }
             }
             Some("download") => AppArguments::Download {
-                day: args.free_from_str()?,
+                puzzle: puzzle(year()?, args.free_from_str()?)?,
             },
             Some("read") => AppArguments::Read {
-                day: args.free_from_str()?,
+                puzzle: puzzle(year()?, args.free_from_str()?)?,
+            },
+            Some("examples") => AppArguments::Examples {
+                puzzle: puzzle(year()?, args.free_from_str()?)?,
+                pick: args.contains("--pick"),
+                overwrite: args.contains("--overwrite"),
             },
             Some("scaffold") => AppArguments::Scaffold {
-                day: args.free_from_str()?,
+                puzzle: puzzle(year()?, args.free_from_str()?)?,
                 download: args.contains("--download"),
                 overwrite: args.contains("--overwrite"),
             },
             Some("solve") => AppArguments::Solve {
-                day: args.free_from_str()?,
+                puzzle: puzzle(year()?, args.free_from_str()?)?,
                 release: args.contains("--release"),
                 submit: args.opt_value_from_str("--submit")?,
                 dhat: args.contains("--dhat"),
+                memory: args.contains("--memory"),
+                viz: match args.opt_value_from_str("--viz-format")? {
+                    Some(target) => Some(target),
+                    None => args.contains("--viz").then_some(Target::Terminal),
+                },
+            },
+            Some("watch") => AppArguments::Watch {
+                puzzle: puzzle(year()?, args.free_from_str()?)?,
+                release: args.contains("--release"),
             },
             #[cfg(feature = "today")]
             Some("today") => AppArguments::Today,
@@ -96,44 +192,87 @@ mod args {
 }
 
 fn main() {
+    #[cfg(feature = "registry")]
+    advent_of_code::template::registry::register(registry::SOLUTIONS);
+
     match parse() {
         Err(err) => {
             eprintln!("Error: {err}");
             std::process::exit(1);
         }
         Ok(args) => match args {
-            AppArguments::All { release } => all::handle(release),
-            AppArguments::Time { day, all, store } => time::handle(day, all, store),
-            AppArguments::Download { day } => download::handle(day),
-            AppArguments::Read { day } => read::handle(day),
-            AppArguments::Scaffold {
+            AppArguments::All {
+                year,
+                release,
+                jobs,
+            } => all::handle(year, release, jobs),
+            AppArguments::CheckAnswers {
+                year,
+                release,
+                jobs,
+            } => check_answers::handle(year, release, jobs),
+            AppArguments::Time {
+                year,
+                day,
+                all,
+                store,
+                budget,
+                threshold,
+                fail_on_regression,
+                jobs,
+            } => time::handle(
+                year,
                 day,
+                all,
+                store,
+                budget,
+                threshold,
+                fail_on_regression,
+                jobs,
+            ),
+            AppArguments::History { puzzle } => time::handle_history(puzzle),
+            AppArguments::Watch { puzzle, release } => watch::handle(puzzle, release),
+            AppArguments::Download { puzzle } => download::handle(puzzle),
+            AppArguments::Read { puzzle } => read::handle(puzzle),
+            AppArguments::Examples {
+                puzzle,
+                pick,
+                overwrite,
+            } => examples::handle(puzzle, pick, overwrite),
+            AppArguments::Scaffold {
+                puzzle,
                 download,
                 overwrite,
             } => {
-                scaffold::handle(day, overwrite);
+                scaffold::handle(puzzle, overwrite);
                 if download {
-                    download::handle(day);
+                    download::handle(puzzle);
+                    examples::handle(puzzle, false, false);
                 }
             }
             AppArguments::Solve {
-                day,
+                puzzle,
                 release,
                 dhat,
                 submit,
-            } => solve::handle(day, release, dhat, submit),
+                viz,
+                memory,
+            } => solve::handle(puzzle, release, dhat, submit, viz, memory),
             #[cfg(feature = "today")]
             AppArguments::Today => {
-                match Day::today() {
-                    Some(day) => {
-                        scaffold::handle(day, false);
-                        download::handle(day);
-                        read::handle(day)
+                match Year::today().zip(Day::today()) {
+                    Some((year, day)) => {
+                        let puzzle = Puzzle::new(year, day);
+                        scaffold::handle(puzzle, false);
+                        download::handle(puzzle);
+                        examples::handle(puzzle, false, false);
+                        read::handle(puzzle)
                     }
                     None => {
                         eprintln!(
-                            "`today` command can only be run between the 1st and \
-                            the 25th of december. Please use `scaffold` with a specific day."
+                            "`today` command can only be run while an event is running, \
+                            i.e. between the 1st of december and the event's last day. \
+                            Please use `scaffold` with a specific day."
                         );
                         process::exit(1)
                     }
@@ -0,0 +1,314 @@
+//! Parsing helpers for the common input formats.
+//!
+//! All helpers work on a [`Span`], a piece of the input that remembers the whole input.
+//! This way, every error points to the line and column of the input where it happened.
+//!
+//! ```
+//! use advent_of_code::parse::Span;
+//!
+//! let input = "47|53\n97|13\n\n75,47,61\n97,61,53";
+//! let [rules, updates] = Span::new(input).sections()[..] else { panic!() };
+//!
+//! let rules: Vec<(u32, u32)> = rules.lines().map(|l| l.pair("|")).collect::<Result<_, _>>()?;
+//! let updates: Vec<Vec<u32>> = updates.lines().map(|l| l.list(",")).collect::<Result<_, _>>()?;
+//! # Ok::<(), advent_of_code::template::ParseError>(())
+//! ```
+use std::fmt::Display;
+use std::str::FromStr;
+
+use crate::grid::Grid;
+use crate::template::ParseError;
+
+/// A piece of the puzzle input, e.g. a section, a line or a field.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Span<'a> {
+    input: &'a str,
+    text: &'a str,
+}
+
+impl<'a> Span<'a> {
+    /// Creates a span that covers the whole input.
+    pub fn new(input: &'a str) -> Self {
+        Self { input, text: input }
+    }
+
+    pub fn as_str(self) -> &'a str {
+        self.text
+    }
+
+    /// Creates an error that points to the start of this span.
+    pub fn error(self, message: impl Into<String>) -> ParseError {
+        ParseError::at_slice(self.input, self.text, message)
+    }
+
+    /// Returns the lines of this span, without line endings.
+    pub fn lines(self) -> impl Iterator<Item = Span<'a>> {
+        self.text.lines().map(move |text| self.sub(text))
+    }
+
+    /// Splits this span into sections that are separated by blank lines.
+    pub fn sections(self) -> Vec<Span<'a>> {
+        let mut sections = vec![];
+        let mut start: Option<usize> = None;
+        let mut end = 0;
+        let mut offset = 0;
+
+        for line in self.text.split_inclusive('\n') {
+            if line.trim().is_empty() {
+                if let Some(start) = start.take() {
+                    sections.push(self.sub(&self.text[start..end]));
+                }
+            } else {
+                start.get_or_insert(offset);
+                end = offset + line.trim_end().len();
+            }
+            offset += line.len();
+        }
+
+        if let Some(start) = start {
+            sections.push(self.sub(&self.text[start..end]));
+        }
+
+        sections
+    }
+
+    /// Splits this span at `separator`.
+    pub fn split(self, separator: &'a str) -> impl Iterator<Item = Span<'a>> {
+        self.text.split(separator).map(move |text| self.sub(text))
+    }
+
+    /// Parses the trimmed span, e.g. `" 42 "` as `42`.
+    pub fn parse<T>(self) -> Result<T, ParseError>
+    where
+        T: FromStr,
+        T::Err: Display,
+    {
+        let text = self.text.trim();
+        text.parse().map_err(|e| {
+            self.sub(text)
+                .error(format!("could not parse `{text}`: {e}"))
+        })
+    }
+
+    /// Parses a record of two fields, e.g. `47|53` with the separator `|`.
+    pub fn pair<A, B>(self, separator: &'a str) -> Result<(A, B), ParseError>
+    where
+        A: FromStr,
+        A::Err: Display,
+        B: FromStr,
+        B::Err: Display,
+    {
+        let (a, b) = self
+            .text
+            .split_once(separator)
+            .ok_or_else(|| self.error(format!("expected two fields separated by `{separator}`")))?;
+        Ok((self.sub(a).parse()?, self.sub(b).parse()?))
+    }
+
+    /// Parses a record of three fields, e.g. `1,2,3` with the separator `,`.
+    pub fn triple<A, B, C>(self, separator: &'a str) -> Result<(A, B, C), ParseError>
+    where
+        A: FromStr,
+        A::Err: Display,
+        B: FromStr,
+        B::Err: Display,
+        C: FromStr,
+        C::Err: Display,
+    {
+        let fields: Vec<Span> = self.split(separator).collect();
+        let [a, b, c] = fields[..] else {
+            return Err(self.error(format!(
+                "expected three fields separated by `{separator}`, found {}",
+                fields.len()
+            )));
+        };
+        Ok((a.parse()?, b.parse()?, c.parse()?))
+    }
+
+    /// Parses a list of fields, e.g. `75,47,61` with the separator `,`. Empty fields are skipped.
+    pub fn list<T>(self, separator: &'a str) -> Result<Vec<T>, ParseError>
+    where
+        T: FromStr,
+        T::Err: Display,
+    {
+        self.split(separator)
+            .filter(|field| !field.text.trim().is_empty())
+            .map(Span::parse)
+            .collect()
+    }
+
+    /// Extracts all integers, including their sign, e.g. `[-3, 4]` from `x=-3, y=4`.
+    pub fn signed<T>(self) -> Result<Vec<T>, ParseError>
+    where
+        T: FromStr,
+        T::Err: Display,
+    {
+        self.integers(true)
+    }
+
+    /// Extracts all integers and treats `-` as a separator, e.g. `[2024, 12, 1]` from `2024-12-01`.
+    pub fn unsigned<T>(self) -> Result<Vec<T>, ParseError>
+    where
+        T: FromStr,
+        T::Err: Display,
+    {
+        self.integers(false)
+    }
+
+    /// Parses a line of single digits, e.g. `2333133121` into `[2, 3, 3, 3, 1, ...]`.
+    pub fn digits(self) -> Result<Vec<u32>, ParseError> {
+        let text = self.text.trim_end();
+        text.char_indices()
+            .map(|(i, c)| {
+                c.to_digit(10).ok_or_else(|| {
+                    self.sub(&text[i..])
+                        .error(format!("expected a digit, found `{c}`"))
+                })
+            })
+            .collect()
+    }
+
+    /// Parses a grid of single digits, one row per line.
+    pub fn digit_grid(self) -> Result<Grid<u32>, ParseError> {
+        let mut cells = vec![];
+        let mut width = None;
+
+        for line in self.lines().filter(|line| !line.text.trim().is_empty()) {
+            let row = line.digits()?;
+            let expected = *width.get_or_insert(row.len());
+            if row.len() != expected {
+                return Err(line.error(format!(
+                    "expected a row of {expected} digits, found {}",
+                    row.len()
+                )));
+            }
+            cells.extend(row);
+        }
+
+        match width {
+            Some(width) if width > 0 => Ok(Grid::from_vec(width, cells)),
+            _ => Err(self.error("expected a grid of digits")),
+        }
+    }
+
+    fn integers<T>(self, signed: bool) -> Result<Vec<T>, ParseError>
+    where
+        T: FromStr,
+        T::Err: Display,
+    {
+        let bytes = self.text.as_bytes();
+        let mut integers = vec![];
+        let mut i = 0;
+
+        while i < bytes.len() {
+            let is_sign =
+                signed && bytes[i] == b'-' && bytes.get(i + 1).is_some_and(u8::is_ascii_digit);
+
+            if !is_sign && !bytes[i].is_ascii_digit() {
+                i += 1;
+                continue;
+            }
+
+            let start = i;
+            i += 1;
+            while i < bytes.len() && bytes[i].is_ascii_digit() {
+                i += 1;
+            }
+            integers.push(self.sub(&self.text[start..i]).parse()?);
+        }
+
+        Ok(integers)
+    }
+
+    fn sub(self, text: &'a str) -> Self {
+        Self {
+            input: self.input,
+            text,
+        }
+    }
+}
+
+impl Display for Span<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.text)
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::Span;
+
+    #[test]
+    fn splits_sections() {
+        let input = "\n47|53\n97|13\n\n\n75,47,61\r\n97,61,53\n";
+        let sections = Span::new(input).sections();
+        assert_eq!(sections.len(), 2);
+        assert_eq!(sections[0].as_str(), "47|53\n97|13");
+        assert_eq!(sections[1].as_str(), "75,47,61\r\n97,61,53");
+        assert_eq!(sections[1].lines().count(), 2);
+    }
+
+    #[test]
+    fn parses_records() {
+        let input = Span::new("47|53\n75,47,61\n190: 10 19\n1x2x3");
+        let lines: Vec<Span> = input.lines().collect();
+
+        assert_eq!(lines[0].pair::<u32, u32>("|"), Ok((47, 53)));
+        assert_eq!(lines[1].list::<u32>(","), Ok(vec![75, 47, 61]));
+        assert_eq!(lines[2].pair::<u64, String>(":"), Ok((190, "10 19".into())));
+        assert_eq!(lines[3].triple::<u8, u8, u8>("x"), Ok((1, 2, 3)));
+        assert_eq!(
+            lines[2].split(": ").nth(1).unwrap().list::<u32>(" "),
+            Ok(vec![10, 19])
+        );
+    }
+
+    #[test]
+    fn extracts_integers() {
+        let line = Span::new("p=0,4 v=-3,-3 on 2024-12-01");
+        assert_eq!(line.signed::<i32>(), Ok(vec![0, 4, -3, -3, 2024, -12, -1]));
+        assert_eq!(line.unsigned::<u32>(), Ok(vec![0, 4, 3, 3, 2024, 12, 1]));
+        assert_eq!(Span::new("a - b").signed::<i32>(), Ok(vec![]));
+    }
+
+    #[test]
+    fn parses_digits() {
+        assert_eq!(Span::new("2333\n").digits(), Ok(vec![2, 3, 3, 3]));
+
+        let grid = Span::new("0123\n1234\n8765\n").digit_grid().unwrap();
+        assert_eq!((grid.width(), grid.height()), (4, 3));
+        assert_eq!(grid.row(2), &[8, 7, 6, 5]);
+    }
+
+    #[test]
+    fn reports_positions() {
+        let input = Span::new("3   4\n4   3\n2   x\n");
+
+        let error = input
+            .lines()
+            .map(|line| line.pair::<u32, u32>("   "))
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap_err();
+        assert_eq!((error.line, error.column), (3, 5));
+        assert!(error.message.contains("could not parse `x`"));
+
+        let error = input
+            .lines()
+            .nth(1)
+            .unwrap()
+            .pair::<u32, u32>("|")
+            .unwrap_err();
+        assert_eq!((error.line, error.column), (2, 1));
+
+        let error = Span::new("0123\n12a4").digit_grid().unwrap_err();
+        assert_eq!((error.line, error.column), (2, 3));
+
+        let error = Span::new("012\n1234").digit_grid().unwrap_err();
+        assert_eq!(error.line, 2);
+
+        let error = Span::new("x=99999999999").signed::<i32>().unwrap_err();
+        assert_eq!((error.line, error.column), (1, 3));
+    }
+}
@@ -0,0 +1,578 @@
+//! Graph searches over successor functions.
+//!
+//! Instead of a materialized graph, every search takes a closure that returns the successors of a node.
+//! This works for explicit graphs (e.g. a `HashMap` of edges) as well as for implicit state spaces,
+//! where nodes are e.g. `(Point, Direction)` states that are only created when they are reached.
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap, HashSet, VecDeque};
+use std::hash::Hash;
+use std::ops::Add;
+
+/// A path through a graph and its total cost.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Path<N, C> {
+    /// The nodes of the path, the start and the goal included.
+    pub nodes: Vec<N>,
+    pub cost: C,
+}
+
+/// Outcome of a search for a goal.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Search<N, C> {
+    /// The path to the first goal that was reached, if any.
+    pub path: Option<Path<N, C>>,
+    /// Number of distinct nodes that were reached during the search.
+    pub visited: usize,
+}
+
+/// Costs of weighted searches. [`Default`] must be the zero cost.
+pub trait Cost: Copy + Ord + Default + Add<Output = Self> {}
+
+impl<C: Copy + Ord + Default + Add<Output = C>> Cost for C {}
+
+/* -------------------------------------------------------------------------- */
+
+/// Breadth-first search for the shortest path (by number of steps) from `start` to a node that satisfies `is_goal`.
+pub fn bfs<N, I>(
+    start: N,
+    mut successors: impl FnMut(&N) -> I,
+    mut is_goal: impl FnMut(&N) -> bool,
+) -> Search<N, usize>
+where
+    N: Clone + Eq + Hash,
+    I: IntoIterator<Item = N>,
+{
+    let mut parents: HashMap<N, Option<N>> = HashMap::from([(start.clone(), None)]);
+    let mut queue = VecDeque::from([(start, 0)]);
+
+    while let Some((node, steps)) = queue.pop_front() {
+        if is_goal(&node) {
+            let nodes = reconstruct(&parents, node);
+            return Search {
+                path: Some(Path { nodes, cost: steps }),
+                visited: parents.len(),
+            };
+        }
+
+        for next in successors(&node) {
+            if !parents.contains_key(&next) {
+                parents.insert(next.clone(), Some(node.clone()));
+                queue.push_back((next, steps + 1));
+            }
+        }
+    }
+
+    Search {
+        path: None,
+        visited: parents.len(),
+    }
+}
+
+/// Returns the number of steps from `start` to every reachable node.
+pub fn bfs_distances<N, I>(start: N, mut successors: impl FnMut(&N) -> I) -> HashMap<N, usize>
+where
+    N: Clone + Eq + Hash,
+    I: IntoIterator<Item = N>,
+{
+    let mut distances = HashMap::from([(start.clone(), 0)]);
+    let mut queue = VecDeque::from([(start, 0)]);
+
+    while let Some((node, steps)) = queue.pop_front() {
+        for next in successors(&node) {
+            if !distances.contains_key(&next) {
+                distances.insert(next.clone(), steps + 1);
+                queue.push_back((next, steps + 1));
+            }
+        }
+    }
+
+    distances
+}
+
+/// Depth-first search for any path from `start` to a node that satisfies `is_goal`.
+/// The path is not necessarily the shortest one, its cost is the number of steps.
+pub fn dfs<N, I>(
+    start: N,
+    mut successors: impl FnMut(&N) -> I,
+    mut is_goal: impl FnMut(&N) -> bool,
+) -> Search<N, usize>
+where
+    N: Clone + Eq + Hash,
+    I: IntoIterator<Item = N>,
+{
+    let mut parents: HashMap<N, Option<N>> = HashMap::from([(start.clone(), None)]);
+    let mut stack = vec![start];
+
+    while let Some(node) = stack.pop() {
+        if is_goal(&node) {
+            let nodes = reconstruct(&parents, node);
+            return Search {
+                path: Some(Path {
+                    cost: nodes.len() - 1,
+                    nodes,
+                }),
+                visited: parents.len(),
+            };
+        }
+
+        for next in successors(&node) {
+            if !parents.contains_key(&next) {
+                parents.insert(next.clone(), Some(node.clone()));
+                stack.push(next);
+            }
+        }
+    }
+
+    Search {
+        path: None,
+        visited: parents.len(),
+    }
+}
+
+/// Dijkstra's algorithm: finds the cheapest path from `start` to a node that satisfies `is_goal`.
+/// `successors` returns the neighbors of a node together with the cost of moving there.
+pub fn dijkstra<N, C, I>(
+    start: N,
+    successors: impl FnMut(&N) -> I,
+    is_goal: impl FnMut(&N) -> bool,
+) -> Search<N, C>
+where
+    N: Clone + Eq + Hash,
+    C: Cost,
+    I: IntoIterator<Item = (N, C)>,
+{
+    astar(start, successors, |_| C::default(), is_goal)
+}
+
+/// A* search: like [`dijkstra`], but nodes are explored in the order of their cost plus `heuristic`.
+/// The heuristic must never overestimate the remaining cost to a goal, e.g. the manhattan distance on a grid.
+pub fn astar<N, C, I>(
+    start: N,
+    mut successors: impl FnMut(&N) -> I,
+    mut heuristic: impl FnMut(&N) -> C,
+    mut is_goal: impl FnMut(&N) -> bool,
+) -> Search<N, C>
+where
+    N: Clone + Eq + Hash,
+    C: Cost,
+    I: IntoIterator<Item = (N, C)>,
+{
+    let mut best: HashMap<N, (C, Option<N>)> =
+        HashMap::from([(start.clone(), (C::default(), None))]);
+    let mut queue = BinaryHeap::new();
+    // nodes are kept out of the heap, so that they don't need to implement `Ord`.
+    let mut nodes = vec![start.clone()];
+    queue.push(Reverse((heuristic(&start), C::default(), 0)));
+
+    while let Some(Reverse((_, cost, index))) = queue.pop() {
+        let node = nodes[index].clone();
+
+        if best.get(&node).is_some_and(|(c, _)| *c < cost) {
+            continue;
+        }
+
+        if is_goal(&node) {
+            let parents: HashMap<N, Option<N>> = best
+                .iter()
+                .map(|(n, (_, p))| (n.clone(), p.clone()))
+                .collect();
+            return Search {
+                path: Some(Path {
+                    nodes: reconstruct(&parents, node),
+                    cost,
+                }),
+                visited: best.len(),
+            };
+        }
+
+        for (next, step_cost) in successors(&node) {
+            let next_cost = cost + step_cost;
+            if best.get(&next).is_none_or(|(c, _)| next_cost < *c) {
+                best.insert(next.clone(), (next_cost, Some(node.clone())));
+                queue.push(Reverse((
+                    next_cost + heuristic(&next),
+                    next_cost,
+                    nodes.len(),
+                )));
+                nodes.push(next);
+            }
+        }
+    }
+
+    Search {
+        path: None,
+        visited: best.len(),
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+/// All cheapest paths from a start node to the cheapest goal, see [`all_shortest_paths`].
+#[derive(Debug, Clone)]
+pub struct ShortestPaths<N, C> {
+    pub start: N,
+    /// The goals that are reached at the lowest cost.
+    pub goals: Vec<N>,
+    pub cost: C,
+    /// Number of distinct nodes that were reached during the search.
+    pub visited: usize,
+    predecessors: HashMap<N, Vec<N>>,
+}
+
+impl<N: Clone + Eq + Hash, C> ShortestPaths<N, C> {
+    /// Returns the nodes that lie on any of the cheapest paths.
+    pub fn nodes(&self) -> HashSet<N> {
+        let mut nodes: HashSet<N> = self.goals.iter().cloned().collect();
+        let mut stack = self.goals.clone();
+
+        while let Some(node) = stack.pop() {
+            for previous in self.predecessors.get(&node).into_iter().flatten() {
+                if nodes.insert(previous.clone()) {
+                    stack.push(previous.clone());
+                }
+            }
+        }
+
+        nodes
+    }
+
+    /// Enumerates all cheapest paths. Their number can grow exponentially, prefer [`Self::nodes`] where possible.
+    pub fn paths(&self) -> Vec<Vec<N>> {
+        let mut paths = vec![];
+        for goal in &self.goals {
+            self.collect_paths(vec![goal.clone()], &mut paths);
+        }
+        paths
+    }
+
+    fn collect_paths(&self, mut suffix: Vec<N>, paths: &mut Vec<Vec<N>>) {
+        let node = suffix.last().unwrap();
+
+        if *node == self.start {
+            suffix.reverse();
+            paths.push(suffix);
+            return;
+        }
+
+        for previous in self.predecessors.get(node).into_iter().flatten() {
+            let mut next = suffix.clone();
+            next.push(previous.clone());
+            self.collect_paths(next, paths);
+        }
+    }
+}
+
+/// Like [`dijkstra`], but keeps track of every cheapest path instead of a single one.
+pub fn all_shortest_paths<N, C, I>(
+    start: N,
+    mut successors: impl FnMut(&N) -> I,
+    mut is_goal: impl FnMut(&N) -> bool,
+) -> Option<ShortestPaths<N, C>>
+where
+    N: Clone + Eq + Hash,
+    C: Cost,
+    I: IntoIterator<Item = (N, C)>,
+{
+    let mut costs: HashMap<N, C> = HashMap::from([(start.clone(), C::default())]);
+    let mut predecessors: HashMap<N, Vec<N>> = HashMap::new();
+    let mut queue = BinaryHeap::from([Reverse((C::default(), 0))]);
+    let mut nodes = vec![start.clone()];
+    let mut goals: Vec<N> = vec![];
+    let mut goal_cost: Option<C> = None;
+
+    while let Some(Reverse((cost, index))) = queue.pop() {
+        if goal_cost.is_some_and(|goal_cost| cost > goal_cost) {
+            break;
+        }
+
+        let node = nodes[index].clone();
+        if costs.get(&node).is_some_and(|c| *c < cost) {
+            continue;
+        }
+
+        if is_goal(&node) {
+            goal_cost = Some(cost);
+            if !goals.contains(&node) {
+                goals.push(node);
+            }
+            continue;
+        }
+
+        for (next, step_cost) in successors(&node) {
+            let next_cost = cost + step_cost;
+            match costs.get(&next) {
+                Some(c) if next_cost > *c => {}
+                Some(c) if next_cost == *c => {
+                    predecessors.entry(next).or_default().push(node.clone());
+                }
+                _ => {
+                    costs.insert(next.clone(), next_cost);
+                    predecessors.insert(next.clone(), vec![node.clone()]);
+                    queue.push(Reverse((next_cost, nodes.len())));
+                    nodes.push(next);
+                }
+            }
+        }
+    }
+
+    Some(ShortestPaths {
+        start,
+        goals,
+        cost: goal_cost?,
+        visited: costs.len(),
+        predecessors,
+    })
+}
+
+/* -------------------------------------------------------------------------- */
+
+/// Returns all nodes that are reachable from `start`, `start` included.
+pub fn flood_fill<N, I>(start: N, mut successors: impl FnMut(&N) -> I) -> HashSet<N>
+where
+    N: Clone + Eq + Hash,
+    I: IntoIterator<Item = N>,
+{
+    let mut reached = HashSet::from([start.clone()]);
+    let mut stack = vec![start];
+
+    while let Some(node) = stack.pop() {
+        for next in successors(&node) {
+            if reached.insert(next.clone()) {
+                stack.push(next);
+            }
+        }
+    }
+
+    reached
+}
+
+/// Groups `nodes` into connected components, e.g. the regions of equal cells on a grid.
+/// `successors` should be symmetric, i.e. describe an undirected graph.
+pub fn connected_components<N, I>(
+    nodes: impl IntoIterator<Item = N>,
+    mut successors: impl FnMut(&N) -> I,
+) -> Vec<HashSet<N>>
+where
+    N: Clone + Eq + Hash,
+    I: IntoIterator<Item = N>,
+{
+    let mut seen: HashSet<N> = HashSet::new();
+    let mut components = vec![];
+
+    for node in nodes {
+        if seen.contains(&node) {
+            continue;
+        }
+        let component = flood_fill(node, &mut successors);
+        seen.extend(component.iter().cloned());
+        components.push(component);
+    }
+
+    components
+}
+
+/// Sorts `nodes` so that every node comes before its successors.
+/// Returns a node that is part of a cycle if there is no such order.
+pub fn topological_sort<N, I>(
+    nodes: impl IntoIterator<Item = N>,
+    mut successors: impl FnMut(&N) -> I,
+) -> Result<Vec<N>, N>
+where
+    N: Clone + Eq + Hash,
+    I: IntoIterator<Item = N>,
+{
+    #[derive(PartialEq)]
+    enum Mark {
+        InProgress,
+        Done,
+    }
+
+    let mut marks: HashMap<N, Mark> = HashMap::new();
+    let mut sorted = vec![];
+
+    for root in nodes {
+        if marks.contains_key(&root) {
+            continue;
+        }
+
+        // iterative post-order traversal, the flag marks nodes whose successors were visited.
+        let mut stack = vec![(root, false)];
+        while let Some((node, expanded)) = stack.pop() {
+            if expanded {
+                marks.insert(node.clone(), Mark::Done);
+                sorted.push(node);
+                continue;
+            }
+
+            match marks.get(&node) {
+                Some(Mark::Done) => continue,
+                Some(Mark::InProgress) => return Err(node),
+                None => {}
+            }
+
+            marks.insert(node.clone(), Mark::InProgress);
+            stack.push((node.clone(), true));
+
+            for next in successors(&node) {
+                match marks.get(&next) {
+                    Some(Mark::InProgress) => return Err(next),
+                    Some(Mark::Done) => {}
+                    None => stack.push((next, false)),
+                }
+            }
+        }
+    }
+
+    sorted.reverse();
+    Ok(sorted)
+}
+
+/// Follows the parents from `node` back to the start and returns the path in order.
+fn reconstruct<N: Clone + Eq + Hash>(parents: &HashMap<N, Option<N>>, node: N) -> Vec<N> {
+    let mut path = vec![node];
+    while let Some(Some(parent)) = parents.get(path.last().unwrap()) {
+        path.push(parent.clone());
+    }
+    path.reverse();
+    path
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::{
+        all_shortest_paths, astar, bfs, bfs_distances, connected_components, dfs, dijkstra,
+        flood_fill, topological_sort,
+    };
+    use crate::grid::{Grid, Point};
+    use std::collections::HashMap;
+
+    const MAZE: &str = "\
+S..#....
+.#.#.##.
+.#...#..
+.####.#.
+......#E";
+
+    fn maze() -> Grid<char> {
+        MAZE.parse().unwrap()
+    }
+
+    fn open_neighbors(grid: &Grid<char>, point: Point) -> Vec<Point> {
+        grid.neighbors4(point).filter(|p| grid[*p] != '#').collect()
+    }
+
+    #[test]
+    fn finds_shortest_paths_with_bfs() {
+        let grid = maze();
+        let goal = grid.find(&'E').unwrap();
+        let search = bfs(
+            grid.find(&'S').unwrap(),
+            |p| open_neighbors(&grid, *p),
+            |p| *p == goal,
+        );
+
+        let path = search.path.unwrap();
+        assert_eq!(path.cost, 15);
+        assert_eq!(path.nodes.len(), 16);
+        assert_eq!(path.nodes.first(), Some(&Point::new(0, 0)));
+        assert_eq!(path.nodes.last(), Some(&goal));
+        assert!(search.visited >= 16);
+
+        let distances = bfs_distances(Point::new(0, 0), |p| open_neighbors(&grid, *p));
+        assert_eq!(distances[&goal], 15);
+    }
+
+    #[test]
+    fn reports_unreachable_goals() {
+        let search = bfs(
+            0,
+            |n| if *n < 5 { vec![n + 1] } else { vec![] },
+            |n| *n == 10,
+        );
+        assert_eq!(search.path, None);
+        assert_eq!(search.visited, 6);
+
+        let search = dfs(
+            0,
+            |n| if *n < 5 { vec![n + 1] } else { vec![] },
+            |n| *n == 5,
+        );
+        assert_eq!(search.path.unwrap().cost, 5);
+    }
+
+    #[test]
+    fn finds_cheapest_paths() {
+        // the direct edge is more expensive than the detour.
+        let edges = HashMap::from([
+            ('a', vec![('b', 7), ('c', 2)]),
+            ('c', vec![('d', 2)]),
+            ('d', vec![('b', 1)]),
+        ]);
+        let successors = |n: &char| edges.get(n).cloned().unwrap_or_default();
+
+        let path = dijkstra('a', successors, |n| *n == 'b').path.unwrap();
+        assert_eq!(path.cost, 5);
+        assert_eq!(path.nodes, vec!['a', 'c', 'd', 'b']);
+
+        let grid = maze();
+        let goal = grid.find(&'E').unwrap();
+        let search = astar(
+            Point::new(0, 0),
+            |p| open_neighbors(&grid, *p).into_iter().map(|p| (p, 1)),
+            |p| p.manhattan(goal),
+            |p| *p == goal,
+        );
+        assert_eq!(search.path.unwrap().cost, 15);
+    }
+
+    #[test]
+    fn finds_all_shortest_paths() {
+        let grid: Grid<char> = "S..\n...\n..E".parse().unwrap();
+        let goal = Point::new(2, 2);
+        let paths = all_shortest_paths(
+            Point::new(0, 0),
+            |p| grid.neighbors4(*p).map(|p| (p, 1)).collect::<Vec<_>>(),
+            |p| *p == goal,
+        )
+        .unwrap();
+
+        assert_eq!(paths.cost, 4);
+        assert_eq!(paths.paths().len(), 6);
+        assert_eq!(paths.nodes().len(), 9);
+    }
+
+    #[test]
+    fn fills_regions() {
+        let grid: Grid<char> = "AAB\nABB\nCCB".parse().unwrap();
+        let same_neighbors = |p: &Point| {
+            grid.neighbors4(*p)
+                .filter(|n| grid[*n] == grid[*p])
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(flood_fill(Point::new(2, 0), same_neighbors).len(), 4);
+
+        let mut sizes: Vec<usize> = connected_components(grid.points(), same_neighbors)
+            .iter()
+            .map(|c| c.len())
+            .collect();
+        sizes.sort_unstable();
+        assert_eq!(sizes, vec![2, 3, 4]);
+    }
+
+    #[test]
+    fn sorts_topologically() {
+        let edges = HashMap::from([(97, vec![13, 61]), (61, vec![13]), (75, vec![97])]);
+        let successors = |n: &u32| edges.get(n).cloned().unwrap_or_default();
+
+        assert_eq!(
+            topological_sort([13, 61, 97, 75], successors),
+            Ok(vec![75, 97, 61, 13])
+        );
+
+        let cyclic = HashMap::from([(1, vec![2]), (2, vec![3]), (3, vec![1])]);
+        assert!(topological_sort([1], |n: &u32| cyclic[n].clone()).is_err());
+    }
+}
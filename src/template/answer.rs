@@ -0,0 +1,233 @@
+/// Normalization of answers before they are displayed, recorded and submitted.
+use std::fmt::Display;
+
+/// Characters that count as unlit pixels in ASCII-art answers.
+const DARK_PIXELS: [char; 3] = ['.', ' ', '░'];
+
+/// Height of the letters of the standard Advent of Code font.
+const LETTER_HEIGHT: usize = 6;
+
+/// The standard Advent of Code font, one row per line.
+const LETTERS: [(char, [&str; LETTER_HEIGHT]); 18] = [
+    ('A', [".##.", "#..#", "#..#", "####", "#..#", "#..#"]),
+    ('B', ["###.", "#..#", "###.", "#..#", "#..#", "###."]),
+    ('C', [".##.", "#..#", "#...", "#...", "#..#", ".##."]),
+    ('E', ["####", "#...", "###.", "#...", "#...", "####"]),
+    ('F', ["####", "#...", "###.", "#...", "#...", "#..."]),
+    ('G', [".##.", "#..#", "#...", "#.##", "#..#", ".###"]),
+    ('H', ["#..#", "#..#", "####", "#..#", "#..#", "#..#"]),
+    ('I', ["###", ".#.", ".#.", ".#.", ".#.", "###"]),
+    ('J', ["..##", "...#", "...#", "...#", "#..#", ".##."]),
+    ('K', ["#..#", "#.#.", "##..", "#.#.", "#.#.", "#..#"]),
+    ('L', ["#...", "#...", "#...", "#...", "#...", "####"]),
+    ('O', [".##.", "#..#", "#..#", "#..#", "#..#", ".##."]),
+    ('P', ["###.", "#..#", "#..#", "###.", "#...", "#..."]),
+    ('R', ["###.", "#..#", "#..#", "###.", "#.#.", "#..#"]),
+    ('S', [".###", "#...", "#...", ".##.", "...#", "###."]),
+    ('U', ["#..#", "#..#", "#..#", "#..#", "#..#", ".##."]),
+    ('Y', ["#...#", "#...#", ".#.#.", "..#..", "..#..", "..#.."]),
+    ('Z', ["####", "...#", "..#.", ".#..", "#...", "####"]),
+];
+
+/// A normalized answer.
+///
+/// # Display
+/// This value displays as the answer that is submitted.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Answer {
+    /// The answer as submitted, e.g. `6,1` or `RZHFGJCB`.
+    pub value: String,
+    /// The original output of the part, if it was ASCII art that was read into letters.
+    pub art: Option<String>,
+}
+
+impl Answer {
+    /// Normalizes the output of a part:
+    ///  1. surrounding whitespace is trimmed.
+    ///  2. ASCII art in the standard Advent of Code font is read into letters.
+    ///  3. trailing whitespace is trimmed from every line of other multi-line answers.
+    pub fn new(output: &impl Display) -> Self {
+        let output = output.to_string();
+        let lines: Vec<&str> = output
+            .trim_matches('\n')
+            .lines()
+            .map(str::trim_end)
+            .collect();
+
+        if lines.len() <= 1 {
+            return Self {
+                value: output.trim().to_string(),
+                art: None,
+            };
+        }
+
+        match ocr(&lines.join("\n")) {
+            Some(letters) => Self {
+                value: letters,
+                art: Some(lines.join("\n")),
+            },
+            None => Self {
+                value: lines.join("\n"),
+                art: None,
+            },
+        }
+    }
+}
+
+impl Display for Answer {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.value)
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+/// Reads ASCII art in the standard Advent of Code font (6 pixels high) into letters.
+/// Lit pixels can be any character other than `.`, ` ` or `░`.
+/// Returns [`None`] if the art contains shapes that are not a known letter.
+pub fn ocr(art: &str) -> Option<String> {
+    let rows: Vec<Vec<bool>> = art
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| line.chars().map(|c| !DARK_PIXELS.contains(&c)).collect())
+        .collect();
+
+    if rows.len() != LETTER_HEIGHT {
+        return None;
+    }
+
+    let width = rows.iter().map(Vec::len).max()?;
+    let is_lit = |x: usize, y: usize| rows[y].get(x).copied().unwrap_or(false);
+    let is_empty_column = |x: usize| (0..LETTER_HEIGHT).all(|y| !is_lit(x, y));
+
+    let mut letters = String::new();
+    let mut x = 0;
+
+    while x < width {
+        if is_empty_column(x) {
+            x += 1;
+            continue;
+        }
+
+        let start = x;
+        while x < width && !is_empty_column(x) {
+            x += 1;
+        }
+
+        let glyph: Vec<String> = (0..LETTER_HEIGHT)
+            .map(|y| {
+                (start..x)
+                    .map(|x| if is_lit(x, y) { '#' } else { '.' })
+                    .collect()
+            })
+            .collect();
+
+        letters.push(read_letter(&glyph)?);
+    }
+
+    (!letters.is_empty()).then_some(letters)
+}
+
+fn read_letter(glyph: &[String]) -> Option<char> {
+    LETTERS.iter().find_map(|(letter, pattern)| {
+        // letters that end in an empty column (e.g. `B`) are cut off by the segmentation.
+        let matches = pattern.iter().zip(glyph).all(|(expected, actual)| {
+            expected.trim_end_matches('.') == actual.trim_end_matches('.')
+        });
+        let same_width = pattern
+            .iter()
+            .map(|row| row.trim_end_matches('.').len())
+            .max()
+            == glyph
+                .iter()
+                .map(|row| row.trim_end_matches('.').len())
+                .max();
+        (matches && same_width).then_some(*letter)
+    })
+}
+
+/* -------------------------------------------------------------------------- */
+
+/// Displays a list as a comma-separated answer, e.g. `Joined(vec![6, 1])` as `6,1`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Joined<T>(pub Vec<T>);
+
+impl<T> Joined<T> {
+    /// Creates a coordinate answer like `6,1`.
+    pub fn pair(x: T, y: T) -> Self {
+        Self(vec![x, y])
+    }
+}
+
+impl<T> FromIterator<T> for Joined<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        Self(iter.into_iter().collect())
+    }
+}
+
+impl<T: Display> Display for Joined<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (index, value) in self.0.iter().enumerate() {
+            if index > 0 {
+                f.write_str(",")?;
+            }
+            write!(f, "{value}")?;
+        }
+        Ok(())
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::{ocr, Answer, Joined};
+
+    const ART: &str = "
+#..#.####.#....#.....##.
+#..#.#....#....#....#..#
+####.###..#....#....#..#
+#..#.#....#....#....#..#
+#..#.#....#....#....#..#
+#..#.####.####.####..##.
+";
+
+    #[test]
+    fn reads_letters() {
+        assert_eq!(ocr(ART), Some("HELLO".into()));
+        assert_eq!(
+            ocr(&ART.replace('#', "█").replace('.', " ")),
+            Some("HELLO".into())
+        );
+    }
+
+    #[test]
+    fn reads_narrow_and_wide_letters() {
+        let art = "###.#...#\n.#..#...#\n.#...#.#.\n.#....#..\n.#....#..\n###...#..";
+        assert_eq!(ocr(art), Some("IY".into()));
+    }
+
+    #[test]
+    fn rejects_unknown_shapes() {
+        assert_eq!(ocr("#.#\n.#.\n#.#\n.#.\n#.#\n.#."), None);
+        assert_eq!(ocr("####\n#..#\n####"), None);
+    }
+
+    #[test]
+    fn normalizes_answers() {
+        assert_eq!(Answer::new(&" 42\n").value, "42");
+        assert_eq!(Answer::new(&Joined::pair(6, 1)).value, "6,1");
+        assert_eq!(
+            Answer::new(&[1, 2, 3].into_iter().collect::<Joined<_>>()).value,
+            "1,2,3"
+        );
+
+        let answer = Answer::new(&ART);
+        assert_eq!(answer.value, "HELLO");
+        assert_eq!(answer.art.as_deref(), Some(ART.trim()));
+
+        let answer = Answer::new(&"#  \n.#\n");
+        assert_eq!(answer.value, "#\n.#");
+        assert_eq!(answer.art, None);
+    }
+}
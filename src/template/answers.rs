@@ -0,0 +1,364 @@
+/// Module that keeps a registry of accepted answers for real inputs.
+use std::{
+    collections::HashMap,
+    fs,
+    io::{Error, ErrorKind},
+    path::Path,
+    str::FromStr,
+};
+use tinyjson::JsonValue;
+
+use crate::template::{
+    create_data_file,
+    record::{Record, Status, PARSE_PART},
+    Day, Year,
+};
+
+static ANSWERS_FILE_NAME: &str = "answers.json";
+
+/// Represents the accepted answers of a single day.
+#[derive(Clone, Debug, PartialEq)]
+pub struct KnownAnswer {
+    pub day: Day,
+    pub part_1: Option<String>,
+    pub part_2: Option<String>,
+}
+
+/// Represents accepted answers for a set of days.
+/// Can be serialized from / to JSON.
+#[derive(Clone, Debug, Default)]
+pub struct KnownAnswers {
+    pub data: Vec<KnownAnswer>,
+}
+
+/// Result of checking a single part against the registry.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Check {
+    Match,
+    Mismatch {
+        expected: String,
+        actual: String,
+    },
+    /// The part produced an answer but no accepted answer is known.
+    Missing {
+        actual: String,
+    },
+    /// An accepted answer is known but the part did not produce an answer.
+    Unsolved {
+        expected: String,
+    },
+}
+
+impl KnownAnswers {
+    /// Dehydrate answers to a JSON file.
+    pub fn store_file(&self, year: Year) -> Result<(), Error> {
+        let json = JsonValue::from(self.clone());
+        let mut file = create_data_file(year, ANSWERS_FILE_NAME)?;
+        json.format_to(&mut file)
+    }
+
+    /// Rehydrate answers from a JSON file. If not present, returns no answers.
+    /// Fails if the file can not be read or parsed, so that it is not overwritten by a single new answer.
+    pub fn read_from_file(year: Year) -> Result<Self, String> {
+        Self::read_from_path(&year.data_dir().join(ANSWERS_FILE_NAME))
+    }
+
+    fn read_from_path(path: &Path) -> Result<Self, String> {
+        match fs::read_to_string(path) {
+            Ok(content) => KnownAnswers::try_from(content)
+                .map_err(|e| format!("could not parse `{}`: {e}", path.display())),
+            Err(e) if e.kind() == ErrorKind::NotFound => Ok(KnownAnswers::default()),
+            Err(e) => Err(format!("could not read `{}`: {e}", path.display())),
+        }
+    }
+
+    /// Returns the accepted answer of a part, if known.
+    pub fn get(&self, day: Day, part: u8) -> Option<&str> {
+        let answer = self.data.iter().find(|a| a.day == day)?;
+        match part {
+            1 => answer.part_1.as_deref(),
+            2 => answer.part_2.as_deref(),
+            _ => None,
+        }
+    }
+
+    /// Sets the accepted answer of a part, overwriting an existing one.
+    pub fn set(&mut self, day: Day, part: u8, value: &str) {
+        let index = match self.data.iter().position(|a| a.day == day) {
+            Some(index) => index,
+            None => {
+                self.data.push(KnownAnswer {
+                    day,
+                    part_1: None,
+                    part_2: None,
+                });
+                self.data.sort_unstable_by_key(|a| a.day);
+                self.data.iter().position(|a| a.day == day).unwrap()
+            }
+        };
+
+        match part {
+            1 => self.data[index].part_1 = Some(value.into()),
+            2 => self.data[index].part_2 = Some(value.into()),
+            _ => {}
+        }
+    }
+
+    /// Checks the answers of a run against the registry.
+    ///
+    /// Every stored answer is reported, so a day without a solved record counts as unsolved.
+    /// Solved parts without a stored answer are reported as missing. The parse step is skipped.
+    pub fn check(&self, records: &[Record]) -> Vec<(Day, u8, Check)> {
+        let actual = |day: Day, part: u8| {
+            records
+                .iter()
+                .find(|r| r.day == day && r.part == part && r.status == Status::Solved)
+                .and_then(|r| r.answer.as_ref())
+        };
+
+        let mut checks: Vec<(Day, u8, Check)> = self
+            .data
+            .iter()
+            .flat_map(|known| {
+                [(1, &known.part_1), (2, &known.part_2)]
+                    .into_iter()
+                    .filter_map(move |(part, expected)| Some((known.day, part, expected.as_ref()?)))
+            })
+            .map(|(day, part, expected)| {
+                let check = match actual(day, part) {
+                    Some(actual) if actual == expected => Check::Match,
+                    Some(actual) => Check::Mismatch {
+                        expected: expected.clone(),
+                        actual: actual.clone(),
+                    },
+                    None => Check::Unsolved {
+                        expected: expected.clone(),
+                    },
+                };
+
+                (day, part, check)
+            })
+            .collect();
+
+        checks.extend(
+            records
+                .iter()
+                .filter(|r| r.part != PARSE_PART && r.status == Status::Solved)
+                .filter(|r| self.get(r.day, r.part).is_none())
+                .filter_map(|r| {
+                    let actual = r.answer.clone()?;
+                    Some((r.day, r.part, Check::Missing { actual }))
+                }),
+        );
+
+        checks.sort_by_key(|(day, part, _)| (*day, *part));
+        checks
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+impl From<KnownAnswers> for JsonValue {
+    fn from(value: KnownAnswers) -> Self {
+        let mut map: HashMap<String, JsonValue> = HashMap::new();
+
+        map.insert(
+            "data".into(),
+            JsonValue::Array(value.data.iter().map(JsonValue::from).collect()),
+        );
+
+        JsonValue::Object(map)
+    }
+}
+
+impl TryFrom<String> for KnownAnswers {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        let json = JsonValue::from_str(&value).or(Err("not valid JSON file."))?;
+
+        let json_data = json
+            .get::<HashMap<String, JsonValue>>()
+            .ok_or("expected JSON document to be an object.")?
+            .get("data")
+            .ok_or("expected JSON document to have key `data`.")?
+            .get::<Vec<JsonValue>>()
+            .ok_or("expected `json.data` to be an array.")?;
+
+        Ok(KnownAnswers {
+            data: json_data
+                .iter()
+                .map(KnownAnswer::try_from)
+                .collect::<Result<_, _>>()?,
+        })
+    }
+}
+
+impl From<&KnownAnswer> for JsonValue {
+    fn from(value: &KnownAnswer) -> Self {
+        let mut map: HashMap<String, JsonValue> = HashMap::new();
+
+        map.insert("day".into(), JsonValue::String(value.day.to_string()));
+
+        for (key, answer) in [("part_1", &value.part_1), ("part_2", &value.part_2)] {
+            map.insert(
+                key.into(),
+                match answer {
+                    Some(x) => JsonValue::String(x.clone()),
+                    None => JsonValue::Null,
+                },
+            );
+        }
+
+        JsonValue::Object(map)
+    }
+}
+
+impl TryFrom<&JsonValue> for KnownAnswer {
+    type Error = String;
+
+    fn try_from(value: &JsonValue) -> Result<Self, Self::Error> {
+        let json = value
+            .get::<HashMap<String, JsonValue>>()
+            .ok_or("Expected answer to be a JSON object.")?;
+
+        let day = json
+            .get("day")
+            .and_then(|v| v.get::<String>())
+            .and_then(|day| Day::from_str(day).ok())
+            .ok_or("Expected answer.day to be a Day struct.")?;
+
+        let part_1 = json
+            .get("part_1")
+            .map(|v| if v.is_null() { None } else { v.get::<String>() })
+            .ok_or("Expected answer.part_1 to be null or string.")?;
+
+        let part_2 = json
+            .get("part_2")
+            .map(|v| if v.is_null() { None } else { v.get::<String>() })
+            .ok_or("Expected answer.part_2 to be null or string.")?;
+
+        Ok(KnownAnswer {
+            day,
+            part_1: part_1.cloned(),
+            part_2: part_2.cloned(),
+        })
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::{Check, KnownAnswers};
+    use crate::{
+        day,
+        template::{
+            record::{Record, Status},
+            Day,
+        },
+    };
+    use std::{env, fs};
+
+    fn record(day: Day, part: u8, answer: Option<&str>) -> Record {
+        Record {
+            day,
+            part,
+            answer: answer.map(Into::into),
+            nanos: 0.0,
+            samples: 1,
+            status: if answer.is_some() {
+                Status::Solved
+            } else {
+                Status::Unsolved
+            },
+            stats: None,
+            memory: None,
+        }
+    }
+
+    #[test]
+    fn sets_answers() {
+        let mut answers = KnownAnswers::default();
+        answers.set(day!(3), 2, "48");
+        answers.set(day!(1), 1, "11");
+        answers.set(day!(3), 1, "161");
+
+        assert_eq!(answers.data.len(), 2);
+        assert_eq!(answers.data[0].day, day!(1));
+        assert_eq!(answers.get(day!(3), 1), Some("161"));
+        assert_eq!(answers.get(day!(3), 2), Some("48"));
+        assert_eq!(answers.get(day!(1), 2), None);
+    }
+
+    #[test]
+    fn roundtrips_answers() {
+        let mut answers = KnownAnswers::default();
+        answers.set(day!(1), 1, "11");
+
+        let json = tinyjson::JsonValue::from(answers).stringify().unwrap();
+        let parsed = KnownAnswers::try_from(json).unwrap();
+
+        assert_eq!(parsed.get(day!(1), 1), Some("11"));
+        assert_eq!(parsed.get(day!(1), 2), None);
+    }
+
+    #[test]
+    fn checks_records() {
+        let mut answers = KnownAnswers::default();
+        answers.set(day!(1), 1, "11");
+        answers.set(day!(1), 2, "31");
+        answers.set(day!(2), 1, "2");
+        answers.set(day!(4), 2, "7");
+
+        let checks = answers.check(&[
+            record(day!(1), 0, None),
+            record(day!(1), 1, Some("11")),
+            record(day!(1), 2, Some("30")),
+            record(day!(2), 1, None),
+            record(day!(2), 2, Some("4")),
+            record(day!(3), 1, None),
+        ]);
+
+        assert_eq!(
+            checks,
+            vec![
+                (day!(1), 1, Check::Match),
+                (
+                    day!(1),
+                    2,
+                    Check::Mismatch {
+                        expected: "31".into(),
+                        actual: "30".into()
+                    }
+                ),
+                (
+                    day!(2),
+                    1,
+                    Check::Unsolved {
+                        expected: "2".into()
+                    }
+                ),
+                (day!(2), 2, Check::Missing { actual: "4".into() }),
+                (
+                    day!(4),
+                    2,
+                    Check::Unsolved {
+                        expected: "7".into()
+                    }
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn refuses_to_read_corrupt_files() {
+        let path = env::temp_dir().join(format!("aoc-answers-{}.json", std::process::id()));
+        assert_eq!(KnownAnswers::read_from_path(&path).unwrap().data.len(), 0);
+
+        fs::write(&path, "{ \"data\": [").unwrap();
+        let error = KnownAnswers::read_from_path(&path).unwrap_err();
+        let _ = fs::remove_file(&path);
+        assert!(error.starts_with("could not parse"));
+    }
+}
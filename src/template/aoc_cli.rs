@@ -1,125 +1,590 @@
-/// Wrapper module around the "aoc-cli" command-line.
+/// Module that talks to the Advent of Code website over HTTP.
+/// It downloads inputs and puzzle descriptions and submits answers on behalf of the user.
 use std::{
+    env,
     fmt::Display,
-    process::{Command, Output, Stdio},
+    fs, io,
+    path::{Path, PathBuf},
+    time::Duration,
 };
 
-use crate::template::Day;
+use crate::template::Puzzle;
+
+static DEFAULT_BASE_URL: &str = "https://adventofcode.com";
+static SESSION_FILE_NAME: &str = ".adventofcode.session";
+static USER_AGENT: &str = concat!("advent_of_code/", env!("CARGO_PKG_VERSION"), " (ureq)");
 
 #[derive(Debug)]
 pub enum AocCommandError {
-    CommandNotFound,
-    CommandNotCallable,
-    BadExitStatus(Output),
+    SessionNotFound,
+    Unauthorized,
+    RateLimited(Option<Duration>),
+    HttpStatus(u16),
+    Transport(String),
+    UnexpectedResponse(String),
+    IO(io::Error),
 }
 
 impl Display for AocCommandError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
-            AocCommandError::CommandNotFound => write!(f, "aoc-cli is not present in environment."),
-            AocCommandError::CommandNotCallable => write!(f, "aoc-cli could not be called."),
-            AocCommandError::BadExitStatus(_) => {
-                write!(f, "aoc-cli exited with a non-zero status.")
+            AocCommandError::SessionNotFound => write!(
+                f,
+                "no session cookie found. Set `AOC_SESSION` or create \"~/{SESSION_FILE_NAME}\"."
+            ),
+            AocCommandError::Unauthorized => {
+                write!(
+                    f,
+                    "the server rejected the session cookie. It might have expired."
+                )
+            }
+            AocCommandError::RateLimited(Some(wait)) => write!(
+                f,
+                "you gave an answer too recently, wait {}s before trying again.",
+                wait.as_secs()
+            ),
+            AocCommandError::RateLimited(None) => {
+                write!(f, "you are being rate limited, try again later.")
+            }
+            AocCommandError::HttpStatus(404) => write!(
+                f,
+                "the server responded with status 404. The puzzle might not be unlocked yet."
+            ),
+            AocCommandError::HttpStatus(status) => {
+                write!(f, "the server responded with status {status}.")
+            }
+            AocCommandError::Transport(e) => write!(f, "could not reach the server: {e}"),
+            AocCommandError::UnexpectedResponse(e) => {
+                write!(f, "unexpected response from the server: {e}")
             }
+            AocCommandError::IO(e) => write!(f, "could not write file: {e}"),
         }
     }
 }
 
+impl From<io::Error> for AocCommandError {
+    fn from(e: io::Error) -> Self {
+        AocCommandError::IO(e)
+    }
+}
+
+/// Server verdict for a submitted answer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Verdict {
+    Correct,
+    TooHigh,
+    TooLow,
+    Incorrect,
+    AlreadySolved,
+}
+
+impl Display for Verdict {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Verdict::Correct => write!(f, "That's the right answer!"),
+            Verdict::TooHigh => write!(f, "That's not the right answer, your answer is too high."),
+            Verdict::TooLow => write!(f, "That's not the right answer, your answer is too low."),
+            Verdict::Incorrect => write!(f, "That's not the right answer."),
+            Verdict::AlreadySolved => {
+                write!(
+                    f,
+                    "You don't seem to be solving the right level. Did you already complete it?"
+                )
+            }
+        }
+    }
+}
+
+/// A minimal client for the Advent of Code website.
+pub struct Client {
+    agent: ureq::Agent,
+    base_url: String,
+    session: String,
+}
+
+impl Client {
+    /// Creates a client that talks to `base_url` and authenticates with `session`.
+    pub fn new(base_url: &str, session: &str) -> Self {
+        let agent = ureq::AgentBuilder::new()
+            .user_agent(USER_AGENT)
+            .timeout(Duration::from_secs(30))
+            .build();
+
+        Self {
+            agent,
+            base_url: base_url.trim_end_matches('/').to_string(),
+            session: session.trim().to_string(),
+        }
+    }
+
+    /// Creates a client from the environment.
+    /// The base url can be overridden with `AOC_BASE_URL`, the session is read from `AOC_SESSION`,
+    /// the file pointed at by `AOC_SESSION_FILE` or `~/.adventofcode.session`, in that order.
+    pub fn from_env() -> Result<Self, AocCommandError> {
+        let base_url = env::var("AOC_BASE_URL").unwrap_or_else(|_| DEFAULT_BASE_URL.into());
+        let session = get_session().ok_or(AocCommandError::SessionNotFound)?;
+        Ok(Self::new(&base_url, &session))
+    }
+
+    /// Fetches the personal puzzle input.
+    pub fn fetch_input(&self, puzzle: Puzzle) -> Result<String, AocCommandError> {
+        self.get(&format!("{}/input", puzzle_url_path(puzzle)))
+    }
+
+    /// Fetches the puzzle description. Returns the `<article>` elements of the page as HTML.
+    pub fn fetch_puzzle(&self, puzzle: Puzzle) -> Result<String, AocCommandError> {
+        let page = self.get(&puzzle_url_path(puzzle))?;
+        let articles = html::elements(&page, "article");
+
+        if articles.is_empty() {
+            return Err(AocCommandError::UnexpectedResponse(
+                "puzzle page does not contain a description.".into(),
+            ));
+        }
+
+        Ok(articles.join("\n\n"))
+    }
+
+    /// Submits an answer and returns the verdict of the server.
+    pub fn submit_answer(
+        &self,
+        puzzle: Puzzle,
+        part: u8,
+        answer: &str,
+    ) -> Result<Verdict, AocCommandError> {
+        let url = format!("{}{}/answer", self.base_url, puzzle_url_path(puzzle));
+        let response = self
+            .agent
+            .post(&url)
+            .set("Cookie", &format!("session={}", self.session))
+            .send_form(&[("level", &part.to_string()), ("answer", answer)]);
+
+        let page = into_body(response)?;
+        let article = html::elements(&page, "article")
+            .into_iter()
+            .next()
+            .ok_or_else(|| {
+                AocCommandError::UnexpectedResponse(
+                    "answer page does not contain a message.".into(),
+                )
+            })?;
+
+        parse_verdict(&html::to_text(&article))
+    }
+
+    fn get(&self, path: &str) -> Result<String, AocCommandError> {
+        let response = self
+            .agent
+            .get(&format!("{}{path}", self.base_url))
+            .set("Cookie", &format!("session={}", self.session))
+            .call();
+
+        into_body(response)
+    }
+}
+
+fn puzzle_url_path(puzzle: Puzzle) -> String {
+    format!(
+        "/{}/day/{}",
+        puzzle.year.into_inner(),
+        puzzle.day.into_inner()
+    )
+}
+
+fn into_body(response: Result<ureq::Response, ureq::Error>) -> Result<String, AocCommandError> {
+    match response {
+        Ok(response) => response
+            .into_string()
+            .map_err(|e| AocCommandError::Transport(e.to_string())),
+        // the site answers with `400` for requests that need a session but do not have a valid one.
+        Err(ureq::Error::Status(400 | 401 | 403, _)) => Err(AocCommandError::Unauthorized),
+        Err(ureq::Error::Status(429, response)) => Err(AocCommandError::RateLimited(
+            response
+                .header("Retry-After")
+                .and_then(|s| s.parse().ok())
+                .map(Duration::from_secs),
+        )),
+        Err(ureq::Error::Status(status, _)) => Err(AocCommandError::HttpStatus(status)),
+        Err(ureq::Error::Transport(e)) => Err(AocCommandError::Transport(e.to_string())),
+    }
+}
+
+fn parse_verdict(message: &str) -> Result<Verdict, AocCommandError> {
+    if message.contains("That's the right answer") {
+        Ok(Verdict::Correct)
+    } else if message.contains("You gave an answer too recently") {
+        Err(AocCommandError::RateLimited(parse_wait_time(message)))
+    } else if message.contains("You don't seem to be solving the right level") {
+        Ok(Verdict::AlreadySolved)
+    } else if message.contains("your answer is too high") {
+        Ok(Verdict::TooHigh)
+    } else if message.contains("your answer is too low") {
+        Ok(Verdict::TooLow)
+    } else if message.contains("That's not the right answer") {
+        Ok(Verdict::Incorrect)
+    } else {
+        Err(AocCommandError::UnexpectedResponse(message.trim().into()))
+    }
+}
+
+/// Parses messages like `You have 1m 30s left to wait.`
+fn parse_wait_time(message: &str) -> Option<Duration> {
+    let wait = message
+        .split("You have ")
+        .nth(1)?
+        .split(" left to wait")
+        .next()?;
+
+    wait.split_whitespace()
+        .map(|s| match s.split_at(s.len().checked_sub(1)?) {
+            (n, "s") => n.parse::<u64>().ok(),
+            (n, "m") => n.parse::<u64>().ok().map(|n| n * 60),
+            (n, "h") => n.parse::<u64>().ok().map(|n| n * 3600),
+            _ => None,
+        })
+        .sum::<Option<u64>>()
+        .map(Duration::from_secs)
+}
+
 pub fn check() -> Result<(), AocCommandError> {
-    Command::new("aoc")
-        .arg("-V")
-        .output()
-        .map_err(|_| AocCommandError::CommandNotFound)?;
+    get_session().ok_or(AocCommandError::SessionNotFound)?;
     Ok(())
 }
 
-pub fn read(day: Day) -> Result<Output, AocCommandError> {
-    let puzzle_path = get_puzzle_path(day);
+pub fn read(puzzle: Puzzle) -> Result<(), AocCommandError> {
+    let puzzle_path = puzzle.data_path("puzzles", "md");
 
-    let args = build_args(
-        "read",
-        &[
-            "--description-only".into(),
-            "--puzzle-file".into(),
-            puzzle_path,
-        ],
-        day,
-    );
+    let description = Client::from_env()?.fetch_puzzle(puzzle)?;
+    write_file(&puzzle_path, &description)?;
 
-    call_aoc_cli(&args)
+    println!("{}", html::to_text(&description));
+    Ok(())
 }
 
-pub fn download(day: Day) -> Result<Output, AocCommandError> {
-    let input_path = get_input_path(day);
-    let puzzle_path = get_puzzle_path(day);
+pub fn download(puzzle: Puzzle) -> Result<(), AocCommandError> {
+    let input_path = puzzle.data_path("inputs", "txt");
+    let puzzle_path = puzzle.data_path("puzzles", "md");
 
-    let args = build_args(
-        "download",
-        &[
-            "--overwrite".into(),
-            "--input-file".into(),
-            input_path.to_string(),
-            "--puzzle-file".into(),
-            puzzle_path.to_string(),
-        ],
-        day,
-    );
+    let client = Client::from_env()?;
+    let input = client.fetch_input(puzzle)?;
+    let description = client.fetch_puzzle(puzzle)?;
+
+    write_file(&input_path, &input)?;
+    write_file(&puzzle_path, &description)?;
 
-    let output = call_aoc_cli(&args)?;
     println!("---");
-    println!("🎄 Successfully wrote input to \"{}\".", &input_path);
-    println!("🎄 Successfully wrote puzzle to \"{}\".", &puzzle_path);
-    Ok(output)
+    println!(
+        "🎄 Successfully wrote input to \"{}\".",
+        input_path.display()
+    );
+    println!(
+        "🎄 Successfully wrote puzzle to \"{}\".",
+        puzzle_path.display()
+    );
+    Ok(())
 }
 
-pub fn submit(day: Day, part: u8, result: &str) -> Result<Output, AocCommandError> {
-    // workaround: the argument order is inverted for submit.
-    let mut args = build_args("submit", &[], day);
-    args.push(part.to_string());
-    args.push(result.to_string());
-    call_aoc_cli(&args)
+pub fn submit(puzzle: Puzzle, part: u8, result: &str) -> Result<Verdict, AocCommandError> {
+    Client::from_env()?.submit_answer(puzzle, part, result)
 }
 
-fn get_input_path(day: Day) -> String {
-    format!("data/inputs/{day}.txt")
+fn write_file(path: &Path, contents: &str) -> Result<(), io::Error> {
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    fs::write(path, contents)
 }
 
-fn get_puzzle_path(day: Day) -> String {
-    format!("data/puzzles/{day}.md")
+fn get_session() -> Option<String> {
+    if let Some(session) = env::var("AOC_SESSION")
+        .ok()
+        .map(|s| s.trim().to_string())
+        .filter(|s| !s.is_empty())
+    {
+        return Some(session);
+    }
+
+    let path = env::var("AOC_SESSION_FILE")
+        .map(PathBuf::from)
+        .or_else(|_| {
+            env::var("HOME")
+                .or_else(|_| env::var("USERPROFILE"))
+                .map(|home| PathBuf::from(home).join(SESSION_FILE_NAME))
+        });
+
+    path.ok()
+        .and_then(|path| fs::read_to_string(path).ok())
+        .map(|s| s.trim().to_string())
+        .filter(|s| !s.is_empty())
 }
 
-fn get_year() -> Option<u16> {
-    match std::env::var("AOC_YEAR") {
-        Ok(x) => x.parse().ok().or(None),
-        Err(_) => None,
+/// Just enough HTML handling to deal with the markup of the Advent of Code website.
+pub(crate) mod html {
+    /// Returns all elements with the given tag name (including the tags themselves).
+    pub fn elements(page: &str, tag: &str) -> Vec<String> {
+        let open = format!("<{tag}");
+        let close = format!("</{tag}>");
+
+        let mut elements = vec![];
+        let mut rest = page;
+
+        while let Some(start) = rest.find(&open) {
+            let Some(end) = rest[start..].find(&close) else {
+                break;
+            };
+            let end = start + end + close.len();
+            elements.push(rest[start..end].to_string());
+            rest = &rest[end..];
+        }
+
+        elements
     }
-}
 
-fn build_args(command: &str, args: &[String], day: Day) -> Vec<String> {
-    let mut cmd_args = args.to_vec();
+    /// Renders HTML into readable plain text.
+    pub fn to_text(html: &str) -> String {
+        let mut text = String::new();
+        let mut rest = html;
+        let mut in_pre = false;
+
+        while let Some(start) = rest.find('<') {
+            push_text(&mut text, &rest[..start], in_pre);
+
+            let Some(end) = rest[start..].find('>') else {
+                break;
+            };
+            let tag = &rest[start + 1..start + end];
+            let name = tag
+                .trim_start_matches('/')
+                .split(|c: char| c.is_whitespace() || c == '/')
+                .next()
+                .unwrap_or_default();
+            let is_closing = tag.starts_with('/');
 
-    if let Some(year) = get_year() {
-        cmd_args.push("--year".into());
-        cmd_args.push(year.to_string());
+            match (name, is_closing) {
+                ("h2", false) => text.push_str("\n\n## "),
+                ("p" | "h2" | "ul", _) => text.push_str("\n\n"),
+                ("pre", false) => {
+                    in_pre = true;
+                    text.push_str("\n\n");
+                }
+                ("pre", true) => in_pre = false,
+                ("li", false) => text.push_str("\n - "),
+                ("em", _) if !in_pre => text.push('*'),
+                _ => {}
+            }
+
+            rest = &rest[start + end + 1..];
+        }
+
+        push_text(&mut text, rest, in_pre);
+
+        text.split("\n\n")
+            .map(str::trim_end)
+            .filter(|s| !s.trim().is_empty())
+            .collect::<Vec<_>>()
+            .join("\n\n")
+            .trim()
+            .to_string()
     }
 
-    cmd_args.append(&mut vec!["--day".into(), day.to_string(), command.into()]);
+    fn push_text(text: &mut String, s: &str, in_pre: bool) {
+        let s = decode_entities(s);
+        if in_pre {
+            s.lines().for_each(|line| {
+                text.push_str("    ");
+                text.push_str(line);
+                text.push('\n');
+            });
+        } else {
+            text.push_str(&s.replace('\n', " "));
+        }
+    }
+
+    /// Removes all tags from an HTML fragment and decodes its entities.
+    pub fn strip_tags(html: &str) -> String {
+        let mut text = String::new();
+        let mut rest = html;
+
+        while let Some(start) = rest.find('<') {
+            text.push_str(&rest[..start]);
+            let Some(end) = rest[start..].find('>') else {
+                break;
+            };
+            rest = &rest[start + end + 1..];
+        }
+
+        text.push_str(rest);
+        decode_entities(&text)
+    }
 
-    cmd_args
+    pub fn decode_entities(s: &str) -> String {
+        s.replace("&lt;", "<")
+            .replace("&gt;", ">")
+            .replace("&quot;", "\"")
+            .replace("&#39;", "'")
+            .replace("&apos;", "'")
+            .replace("&amp;", "&")
+    }
 }
 
-fn call_aoc_cli(args: &[String]) -> Result<Output, AocCommandError> {
-    // println!("Calling >aoc with: {}", args.join(" "));
-    let output = Command::new("aoc")
-        .args(args)
-        .stdout(Stdio::inherit())
-        .stderr(Stdio::inherit())
-        .output()
-        .map_err(|_| AocCommandError::CommandNotCallable)?;
+/* -------------------------------------------------------------------------- */
 
-    if output.status.success() {
-        Ok(output)
-    } else {
-        Err(AocCommandError::BadExitStatus(output))
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::{AocCommandError, Client, Verdict};
+    use crate::{day, template::Puzzle, year};
+    use std::{
+        io::{BufRead, BufReader, Read, Write},
+        net::TcpListener,
+        thread,
+    };
+
+    /// Starts a server on a random local port that answers a single request with `status` and `body`.
+    /// Returns the base url of the server and a handle that resolves to the received request.
+    fn mock_server(status: u16, body: &'static str) -> (String, thread::JoinHandle<String>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let base_url = format!("http://{}", listener.local_addr().unwrap());
+
+        let handle = thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut reader = BufReader::new(stream.try_clone().unwrap());
+
+            let mut request = String::new();
+            let mut content_length = 0;
+            loop {
+                let mut line = String::new();
+                reader.read_line(&mut line).unwrap();
+                if let Some(len) = line.to_lowercase().strip_prefix("content-length:") {
+                    content_length = len.trim().parse().unwrap();
+                }
+                request.push_str(&line);
+                if line == "\r\n" {
+                    break;
+                }
+            }
+
+            let mut request_body = vec![0; content_length];
+            reader.read_exact(&mut request_body).unwrap();
+            request.push_str(&String::from_utf8(request_body).unwrap());
+
+            write!(
+                stream,
+                "HTTP/1.1 {status} Mock\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+                body.len()
+            )
+            .unwrap();
+
+            request
+        });
+
+        (base_url, handle)
+    }
+
+    #[test]
+    fn fetches_input_with_session() {
+        let (base_url, handle) = mock_server(200, "1 2\n3 4\n");
+        let client = Client::new(&base_url, "abc");
+        let input = client
+            .fetch_input(Puzzle::new(year!(2024), day!(1)))
+            .unwrap();
+        let request = handle.join().unwrap();
+
+        assert_eq!(input, "1 2\n3 4\n");
+        assert!(request.starts_with("GET /2024/day/1/input "));
+        assert!(request.contains("session=abc"));
+    }
+
+    #[test]
+    fn fetches_puzzle_articles() {
+        let (base_url, handle) = mock_server(
+            200,
+            "<html><main><article class=\"day-desc\"><h2>--- Day 1 ---</h2><p>Hi</p></article></main></html>",
+        );
+        let client = Client::new(&base_url, "abc");
+        let puzzle = client
+            .fetch_puzzle(Puzzle::new(year!(2024), day!(1)))
+            .unwrap();
+        handle.join().unwrap();
+
+        assert_eq!(
+            puzzle,
+            "<article class=\"day-desc\"><h2>--- Day 1 ---</h2><p>Hi</p></article>"
+        );
+    }
+
+    #[test]
+    fn submits_answers() {
+        let (base_url, handle) = mock_server(
+            200,
+            "<main><article><p>That's the right answer! You are <em>one gold star</em> closer.</p></article></main>",
+        );
+        let client = Client::new(&base_url, "abc");
+        let verdict = client
+            .submit_answer(Puzzle::new(year!(2024), day!(3)), 2, "42")
+            .unwrap();
+        let request = handle.join().unwrap();
+
+        assert_eq!(verdict, Verdict::Correct);
+        assert!(request.starts_with("POST /2024/day/3/answer "));
+        assert!(request.ends_with("level=2&answer=42"));
+    }
+
+    #[test]
+    fn handles_wrong_answers() {
+        let (base_url, handle) = mock_server(
+            200,
+            "<article><p>That's not the right answer; your answer is too high.</p></article>",
+        );
+        let client = Client::new(&base_url, "abc");
+        let verdict = client
+            .submit_answer(Puzzle::new(year!(2024), day!(3)), 1, "9001")
+            .unwrap();
+        handle.join().unwrap();
+
+        assert_eq!(verdict, Verdict::TooHigh);
+    }
+
+    #[test]
+    fn handles_rate_limiting() {
+        let (base_url, handle) = mock_server(
+            200,
+            "<article><p>You gave an answer too recently. You have 1m 5s left to wait.</p></article>",
+        );
+        let client = Client::new(&base_url, "abc");
+        let result = client.submit_answer(Puzzle::new(year!(2024), day!(3)), 1, "1");
+        handle.join().unwrap();
+
+        assert!(matches!(
+            result,
+            Err(AocCommandError::RateLimited(Some(wait))) if wait.as_secs() == 65
+        ));
+    }
+
+    #[test]
+    fn handles_auth_failures() {
+        let (base_url, handle) = mock_server(400, "Puzzle inputs differ by user.");
+        let client = Client::new(&base_url, "expired");
+        let result = client.fetch_input(Puzzle::new(year!(2024), day!(1)));
+        handle.join().unwrap();
+
+        assert!(matches!(result, Err(AocCommandError::Unauthorized)));
+    }
+
+    #[test]
+    fn handles_http_status() {
+        let (base_url, handle) = mock_server(404, "Not Found");
+        let client = Client::new(&base_url, "abc");
+        let result = client.fetch_input(Puzzle::new(year!(2024), day!(25)));
+        handle.join().unwrap();
+
+        assert!(matches!(result, Err(AocCommandError::HttpStatus(404))));
+    }
+
+    #[test]
+    fn renders_html_to_text() {
+        let text = super::html::to_text(
+            "<article><h2>--- Day 1 ---</h2><p>A <em>b</em> &lt;c&gt;.</p><pre><code>1 2\n3 4\n</code></pre></article>",
+        );
+        assert_eq!(text, "## --- Day 1 ---\n\nA *b* <c>.\n\n    1 2\n    3 4");
     }
 }
@@ -0,0 +1,62 @@
+use std::process;
+
+use crate::template::answers::{Check, KnownAnswers};
+use crate::template::run_multi::run_multi;
+use crate::template::{all_days, Year, ANSI_BOLD, ANSI_GREEN, ANSI_RED, ANSI_RESET};
+
+pub fn handle(year: Year, is_release: bool, jobs: usize) {
+    let answers = match KnownAnswers::read_from_file(year) {
+        Ok(answers) => answers,
+        Err(e) => {
+            eprintln!("Can not check answers: {e}");
+            process::exit(1);
+        }
+    };
+    let run = run_multi(
+        year,
+        &all_days(year).collect(),
+        is_release,
+        false,
+        None,
+        jobs,
+    );
+    let checks = answers.check(&run.records);
+
+    println!();
+    println!("{ANSI_BOLD}Known answers{ANSI_RESET}");
+    println!("------");
+
+    let mut failures = 0;
+
+    for (day, part, check) in &checks {
+        match check {
+            Check::Match => println!("Day {day} Part {part}: {ANSI_GREEN}match{ANSI_RESET}"),
+            Check::Mismatch { expected, actual } => {
+                failures += 1;
+                println!(
+                    "Day {day} Part {part}: {ANSI_RED}mismatch{ANSI_RESET} (expected {expected}, got {actual})"
+                );
+            }
+            Check::Missing { actual } => {
+                println!(
+                    "Day {day} Part {part}: missing (got {actual}, no accepted answer stored)"
+                );
+            }
+            Check::Unsolved { expected } => {
+                failures += 1;
+                println!(
+                    "Day {day} Part {part}: {ANSI_RED}unsolved{ANSI_RESET} (expected {expected})"
+                );
+            }
+        }
+    }
+
+    if checks.is_empty() {
+        println!("No solved days or accepted answers found.");
+    }
+
+    if failures > 0 {
+        eprintln!("{failures} part(s) do not match or no longer produce their accepted answer.");
+        process::exit(1);
+    }
+}
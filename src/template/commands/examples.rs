@@ -0,0 +1,209 @@
+use std::fs;
+use std::io::{self, BufRead, Write};
+use std::path::{Path, PathBuf};
+use std::process;
+
+use crate::template::examples::{self, PuzzleExamples};
+use crate::template::{Puzzle, ANSI_BOLD, ANSI_RESET};
+
+/// Number of lines shown per code block when picking examples.
+const PREVIEW_LINES: usize = 4;
+
+/// Writes the examples of the downloaded puzzle description to `data/<year>/examples/` and
+/// pre-fills the expected answers in the tests of the solution.
+pub fn handle(puzzle: Puzzle, pick: bool, overwrite: bool) {
+    let description_path = puzzle.data_path("puzzles", "md");
+    let Ok(description) = fs::read_to_string(&description_path) else {
+        eprintln!(
+            "No puzzle description found at \"{}\". Run `cargo download {}` first.",
+            description_path.display(),
+            puzzle.day
+        );
+        process::exit(1);
+    };
+
+    let examples = PuzzleExamples::parse(&description);
+
+    if examples.blocks.is_empty() {
+        println!("No examples found in the puzzle description.");
+        return;
+    }
+
+    let selected = if pick {
+        pick_examples(&examples)
+    } else {
+        default_selection(&examples)
+    };
+
+    for (index, block) in selected.iter().enumerate() {
+        write_example(
+            &example_path(puzzle, index),
+            &examples.blocks[*block].text,
+            overwrite,
+        );
+    }
+
+    prefill_tests(puzzle, &examples, &selected);
+}
+
+/// Picks the example of each part. Part 2 only gets its own file if it uses a different example than part 1.
+fn default_selection(examples: &PuzzleExamples) -> Vec<usize> {
+    let mut selected: Vec<usize> = vec![];
+
+    for block in [examples.select(1), examples.select(2)]
+        .into_iter()
+        .flatten()
+    {
+        if selected
+            .iter()
+            .all(|x| examples.blocks[*x].text != examples.blocks[block].text)
+        {
+            selected.push(block);
+        }
+    }
+
+    selected
+}
+
+/// The first selected example is written to `<day>.txt`, the following ones to `<day>-2.txt`, `<day>-3.txt` and so on.
+fn example_path(puzzle: Puzzle, index: usize) -> PathBuf {
+    if index == 0 {
+        puzzle.data_path("examples", "txt")
+    } else {
+        puzzle
+            .year
+            .data_dir()
+            .join("examples")
+            .join(format!("{}-{}.txt", puzzle.day, index + 1))
+    }
+}
+
+/// Lists all code blocks and asks which ones to use as examples.
+fn pick_examples(examples: &PuzzleExamples) -> Vec<usize> {
+    for (index, block) in examples.blocks.iter().enumerate() {
+        println!("{ANSI_BOLD}[{}] Part {}{ANSI_RESET}", index + 1, block.part);
+        block
+            .text
+            .lines()
+            .take(PREVIEW_LINES)
+            .for_each(|line| println!("    {line}"));
+        if block.text.lines().count() > PREVIEW_LINES {
+            println!("    …");
+        }
+    }
+
+    prompt_blocks(examples, default_selection(examples))
+}
+
+/// Asks for the blocks to write in order, an empty answer picks `default` and `0` skips all blocks.
+fn prompt_blocks(examples: &PuzzleExamples, default: Vec<usize>) -> Vec<usize> {
+    let default_label = if default.is_empty() {
+        "none".to_string()
+    } else {
+        default
+            .iter()
+            .map(|x| (x + 1).to_string())
+            .collect::<Vec<_>>()
+            .join(" ")
+    };
+
+    loop {
+        print!("Examples to write, separated by spaces [{default_label}, 0 to skip]: ");
+        let _ = io::stdout().flush();
+
+        let mut line = String::new();
+        if io::stdin().lock().read_line(&mut line).unwrap_or(0) == 0 {
+            return default;
+        }
+
+        match line.trim() {
+            "" => return default,
+            "0" => return vec![],
+            x => {
+                let blocks: Option<Vec<usize>> = x
+                    .split(|c: char| c.is_whitespace() || c == ',')
+                    .filter(|n| !n.is_empty())
+                    .map(|n| match n.parse::<usize>() {
+                        Ok(n) if (1..=examples.blocks.len()).contains(&n) => Some(n - 1),
+                        _ => None,
+                    })
+                    .collect();
+
+                match blocks {
+                    Some(blocks) => return blocks,
+                    None => eprintln!("Expecting numbers between 1 and {}.", examples.blocks.len()),
+                }
+            }
+        }
+    }
+}
+
+fn write_example(path: &Path, text: &str, overwrite: bool) {
+    let is_empty = fs::read_to_string(path).map_or(true, |x| x.trim().is_empty());
+
+    if !is_empty && !overwrite {
+        println!(
+            "Skipped example file \"{}\", it already exists. Pass `--overwrite` to replace it.",
+            path.display()
+        );
+        return;
+    }
+
+    if let Some(dir) = path.parent() {
+        let _ = fs::create_dir_all(dir);
+    }
+
+    match fs::write(path, text) {
+        Ok(()) => println!("Wrote example file \"{}\"", path.display()),
+        Err(e) => eprintln!("Failed to write example file: {e}"),
+    }
+}
+
+/// Fills in the expected answers of the scaffolded tests, leaving tests that were already edited untouched.
+/// The test of each part reads the file of the first selected block from that part's description.
+fn prefill_tests(puzzle: Puzzle, examples: &PuzzleExamples, selected: &[usize]) {
+    let module_path = puzzle.bin_path();
+    let Ok(mut source) = fs::read_to_string(&module_path) else {
+        return;
+    };
+    let original = source.clone();
+
+    for part in [1, 2] {
+        let Some(answer) = examples.answer(part) else {
+            continue;
+        };
+
+        match examples::prefill_answer(&source, part, answer) {
+            Some(updated) => {
+                println!("Expecting `{answer}` for the example of part {part}.");
+                source = updated;
+            }
+            None => println!(
+                "Did not pre-fill the answer `{answer}` for part {part}, the test was already edited or expects no number."
+            ),
+        }
+    }
+
+    for part in [1, 2] {
+        let Some(index) = selected
+            .iter()
+            .position(|block| examples.blocks[*block].part == part)
+        else {
+            continue;
+        };
+
+        // the first example is `<day>.txt`, which the scaffolded tests already read.
+        if index > 0 {
+            if let Some(updated) = examples::use_part_example(&source, part, index + 1) {
+                source = updated;
+            }
+        }
+    }
+
+    if source != original {
+        match fs::write(&module_path, source) {
+            Ok(()) => println!("Updated tests in \"{}\"", module_path.display()),
+            Err(e) => eprintln!("Failed to update tests: {e}"),
+        }
+    }
+}
@@ -1,15 +1,15 @@
 use std::process;
 
-use crate::template::{aoc_cli, Day};
+use crate::template::{aoc_cli, Puzzle};
 
-pub fn handle(day: Day) {
-    if aoc_cli::check().is_err() {
-        eprintln!("command \"aoc\" not found or not callable. Try running \"cargo install aoc-cli\" to install it.");
+pub fn handle(puzzle: Puzzle) {
+    if let Err(e) = aoc_cli::check() {
+        eprintln!("Cannot reach Advent of Code: {e}");
         process::exit(1);
     }
 
-    if let Err(e) = aoc_cli::read(day) {
-        eprintln!("failed to call aoc-cli: {e}");
+    if let Err(e) = aoc_cli::read(puzzle) {
+        eprintln!("failed to read puzzle: {e}");
         process::exit(1);
     };
 }
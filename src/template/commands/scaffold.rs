@@ -1,15 +1,17 @@
 use std::{
-    fs::{File, OpenOptions},
+    fs::{self, File, OpenOptions},
     io::Write,
+    path::Path,
     process,
 };
 
-use crate::template::Day;
+use crate::template::{Puzzle, Year};
 
 const MODULE_TEMPLATE: &str =
     include_str!(concat!(env!("CARGO_MANIFEST_DIR"), "/src/template.txt"));
 
-fn safe_create_file(path: &str, overwrite: bool) -> Result<File, std::io::Error> {
+fn safe_create_file(path: &Path, overwrite: bool) -> Result<File, std::io::Error> {
+    create_parent_dir(path)?;
     let mut file = OpenOptions::new();
     if overwrite {
         file.create(true);
@@ -19,7 +21,8 @@ fn safe_create_file(path: &str, overwrite: bool) -> Result<File, std::io::Error>
     file.truncate(true).write(true).open(path)
 }
 
-fn create_file(path: &str) -> Result<File, std::io::Error> {
+fn create_file(path: &Path) -> Result<File, std::io::Error> {
+    create_parent_dir(path)?;
     OpenOptions::new()
         .write(true)
         .create(true)
@@ -27,10 +30,18 @@ fn create_file(path: &str) -> Result<File, std::io::Error> {
         .open(path)
 }
 
-pub fn handle(day: Day, overwrite: bool) {
-    let input_path = format!("data/inputs/{day}.txt");
-    let example_path = format!("data/examples/{day}.txt");
-    let module_path = format!("src/bin/{day}.rs");
+fn create_parent_dir(path: &Path) -> Result<(), std::io::Error> {
+    match path.parent() {
+        Some(dir) => fs::create_dir_all(dir),
+        None => Ok(()),
+    }
+}
+
+pub fn handle(puzzle: Puzzle, overwrite: bool) {
+    let day = puzzle.day;
+    let input_path = puzzle.data_path("inputs", "txt");
+    let example_path = puzzle.data_path("examples", "txt");
+    let module_path = puzzle.bin_path();
 
     let mut file = match safe_create_file(&module_path, overwrite) {
         Ok(file) => file,
@@ -46,7 +57,7 @@ pub fn handle(day: Day, overwrite: bool) {
             .as_bytes(),
     ) {
         Ok(()) => {
-            println!("Created module file \"{}\"", &module_path);
+            println!("Created module file \"{}\"", module_path.display());
         }
         Err(e) => {
             eprintln!("Failed to write module contents: {e}");
@@ -56,7 +67,7 @@ pub fn handle(day: Day, overwrite: bool) {
 
     match create_file(&input_path) {
         Ok(_) => {
-            println!("Created empty input file \"{}\"", &input_path);
+            println!("Created empty input file \"{}\"", input_path.display());
         }
         Err(e) => {
             eprintln!("Failed to create input file: {e}");
@@ -66,7 +77,7 @@ pub fn handle(day: Day, overwrite: bool) {
 
     match create_file(&example_path) {
         Ok(_) => {
-            println!("Created empty example file \"{}\"", &example_path);
+            println!("Created empty example file \"{}\"", example_path.display());
         }
         Err(e) => {
             eprintln!("Failed to create example file: {e}");
@@ -75,5 +86,12 @@ pub fn handle(day: Day, overwrite: bool) {
     }
 
     println!("---");
-    println!("🎄 Type `cargo solve {day}` to run your solution.");
+    if Year::from_env() == Some(puzzle.year) {
+        println!("🎄 Type `cargo solve {day}` to run your solution.");
+    } else {
+        println!(
+            "🎄 Type `cargo solve {day} --year {}` to run your solution.",
+            puzzle.year
+        );
+    }
 }
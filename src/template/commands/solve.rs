@@ -1,21 +1,39 @@
 use std::process::{Command, Stdio};
 
-use crate::template::Day;
+use crate::template::Puzzle;
+use crate::viz::{Target, VIZ_ARG};
 
-pub fn handle(day: Day, release: bool, dhat: bool, submit_part: Option<u8>) {
-    let mut cmd_args = vec!["run".to_string(), "--bin".to_string(), day.to_string()];
+pub fn handle(
+    puzzle: Puzzle,
+    release: bool,
+    dhat: bool,
+    submit_part: Option<u8>,
+    viz: Option<Target>,
+    memory: bool,
+) {
+    let mut cmd_args = vec!["run".to_string(), "--bin".to_string(), puzzle.to_string()];
+    let mut features = vec![];
 
     if dhat {
-        cmd_args.extend([
-            "--profile".to_string(),
-            "dhat".to_string(),
-            "--features".to_string(),
-            "dhat-heap".to_string(),
-        ]);
+        cmd_args.extend(["--profile".to_string(), "dhat".to_string()]);
+        features.push("dhat-heap");
     } else if release {
         cmd_args.push("--release".to_string());
     }
 
+    if viz.is_some() {
+        features.push("viz");
+    }
+
+    if memory {
+        features.push("memory");
+    }
+
+    if !features.is_empty() {
+        cmd_args.push("--features".to_string());
+        cmd_args.push(features.join(","));
+    }
+
     cmd_args.push("--".to_string());
 
     if let Some(submit_part) = submit_part {
@@ -23,6 +41,11 @@ pub fn handle(day: Day, release: bool, dhat: bool, submit_part: Option<u8>) {
         cmd_args.push(submit_part.to_string());
     }
 
+    if let Some(viz) = viz {
+        cmd_args.push(VIZ_ARG.to_string());
+        cmd_args.push(viz.to_string());
+    }
+
     let mut cmd = Command::new("cargo")
         .args(&cmd_args)
         .stdout(Stdio::inherit())
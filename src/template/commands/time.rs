@@ -1,34 +1,79 @@
 use std::collections::HashSet;
+use std::process;
+use std::time::Duration;
 
+use crate::template::history::{self, Environment, History};
+use crate::template::regression::{self, DEFAULT_THRESHOLD};
 use crate::template::run_multi::run_multi;
 use crate::template::timings::Timings;
-use crate::template::{all_days, readme_benchmarks, Day};
+use crate::template::{all_days, readme_benchmarks, Day, Puzzle, Year};
 
-pub fn handle(day: Option<Day>, run_all: bool, store: bool) {
-    let stored_timings = Timings::read_from_file();
+#[allow(clippy::too_many_arguments)]
+pub fn handle(
+    year: Year,
+    day: Option<Day>,
+    run_all: bool,
+    store: bool,
+    budget: Option<Duration>,
+    threshold: Option<f64>,
+    fail_on_regression: bool,
+    jobs: usize,
+) {
+    // concurrent days compete for the cpu, which would skew the stored timings and regression checks.
+    if jobs > 1 {
+        eprintln!("Ignoring `--jobs {jobs}`, benchmarks always run one day at a time.");
+    }
+
+    let stored_timings = Timings::read_from_file(year);
 
     let days_to_run = day.map_or_else(
         || {
             if run_all {
-                all_days().collect()
+                all_days(year).collect()
             } else {
                 // when the `--all` flag is not set, filter out days that are fully benched.
-                all_days()
-                    .filter(|day| !stored_timings.is_day_complete(*day))
+                all_days(year)
+                    .filter(|day| !stored_timings.is_day_complete(day))
                     .collect()
             }
         },
         |day| HashSet::from([day]),
     );
 
-    let timings = run_multi(&days_to_run, true, true).unwrap();
+    let timings = run_multi(year, &days_to_run, true, true, budget, 1)
+        .timings
+        .unwrap();
+
+    let threshold = threshold.unwrap_or(DEFAULT_THRESHOLD);
+    let deltas = regression::compare(&stored_timings, &timings, threshold);
+    regression::print_deltas(&deltas, threshold);
+
+    // a failed run must not become the baseline of the next one.
+    let has_regressed = fail_on_regression && deltas.iter().any(regression::Delta::is_regression);
+    let mut has_failed = false;
 
-    if store {
+    if store && has_regressed {
+        eprintln!("Not storing benchmarks because of performance regressions.");
+    } else if store {
         let merged_timings = stored_timings.merge(&timings);
-        merged_timings.store_file().unwrap();
+        merged_timings.store_file(year).unwrap();
+
+        match History::read_from_file(year) {
+            Ok(mut history) => {
+                history.append(&Environment::capture(true), &timings);
+                if let Err(e) = history.store_file(year) {
+                    eprintln!("Failed to store benchmark history: {e}");
+                }
+            }
+            Err(e) => {
+                // the history is append-only, overwriting it would lose all previous runs.
+                eprintln!("Not storing benchmark history: {e}");
+                has_failed = true;
+            }
+        }
 
         println!();
-        match readme_benchmarks::update(merged_timings) {
+        match readme_benchmarks::update() {
             Ok(()) => {
                 println!("Stored updated benchmarks.");
             }
@@ -37,4 +82,24 @@ pub fn handle(day: Option<Day>, run_all: bool, store: bool) {
             }
         }
     }
+
+    if has_regressed {
+        eprintln!("Failing because of performance regressions.");
+        process::exit(1);
+    }
+
+    if has_failed {
+        process::exit(1);
+    }
+}
+
+/// Show how the stored benchmarks of a day evolved over time.
+pub fn handle_history(puzzle: Puzzle) {
+    match History::read_from_file(puzzle.year) {
+        Ok(history) => history::print_day(&history, puzzle.day),
+        Err(e) => {
+            eprintln!("Could not show the benchmark history: {e}");
+            process::exit(1);
+        }
+    }
 }
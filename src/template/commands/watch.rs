@@ -0,0 +1,113 @@
+use std::process::Command;
+use std::thread;
+use std::time::Duration;
+
+use crate::template::history::current_timestamp;
+use crate::template::record::{Status, PARSE_PART};
+use crate::template::run_multi::child_commands;
+use crate::template::stats::format_nanos;
+use crate::template::watch::{TestSummary, WatchedFiles};
+use crate::template::{Puzzle, ANSI_BOLD, ANSI_GREEN, ANSI_ITALIC, ANSI_RED, ANSI_RESET};
+
+/// How often the watched files are checked for changes.
+const POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+/// Re-runs the tests and the solution of `puzzle` whenever one of its files changes.
+pub fn handle(puzzle: Puzzle, release: bool) {
+    let mut files = WatchedFiles::new(puzzle);
+
+    println!("Watching {puzzle} for changes. Press Ctrl+C to stop.");
+
+    loop {
+        if files.changed() {
+            run_once(puzzle, release);
+        }
+        thread::sleep(POLL_INTERVAL);
+    }
+}
+
+fn run_once(puzzle: Puzzle, release: bool) {
+    println!();
+    println!(
+        "{ANSI_BOLD}{puzzle}{ANSI_RESET} {ANSI_ITALIC}{}{ANSI_RESET}",
+        current_timestamp()
+    );
+    println!("------");
+
+    run_tests(puzzle);
+    run_solution(puzzle, release);
+}
+
+fn run_tests(puzzle: Puzzle) {
+    let output = match Command::new("cargo")
+        .args(["test", "--quiet", "--bin", &puzzle.to_string()])
+        .output()
+    {
+        Ok(output) => output,
+        Err(e) => {
+            eprintln!("Failed to run tests: {e}");
+            return;
+        }
+    };
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+
+    match TestSummary::parse(&stdout) {
+        None => {
+            println!("Tests: {ANSI_RED}build failed{ANSI_RESET}");
+            eprint!("{}", String::from_utf8_lossy(&output.stderr));
+        }
+        Some(summary) if summary.failed > 0 => {
+            println!(
+                "Tests: {ANSI_RED}{} failed{ANSI_RESET}, {} passed",
+                summary.failed, summary.passed
+            );
+            for name in &summary.failures {
+                println!("  ✗ {name}");
+            }
+        }
+        Some(summary) => {
+            println!("Tests: {ANSI_GREEN}{} passed{ANSI_RESET}", summary.passed);
+        }
+    }
+}
+
+fn run_solution(puzzle: Puzzle, release: bool) {
+    if !puzzle.data_path("inputs", "txt").exists() {
+        println!("Solution: no input, run `cargo download {}`", puzzle.day);
+        return;
+    }
+
+    let run = match child_commands::run_solution_captured(puzzle, release) {
+        Ok(run) => run,
+        Err(e) => {
+            eprintln!("Failed to run solution: {e:?}");
+            return;
+        }
+    };
+
+    if run.records.is_empty() {
+        println!("Solution: {ANSI_RED}failed{ANSI_RESET}");
+        eprint!("{}", run.stderr);
+        return;
+    }
+
+    for record in &run.records {
+        let time = format_nanos(record.nanos);
+        let label = match record.part {
+            PARSE_PART => "Parse".to_string(),
+            part => format!("Part {part}"),
+        };
+
+        match (&record.status, &record.answer) {
+            (Status::Error { message }, _) => {
+                println!("{label}: ✖ {ANSI_RED}{message}{ANSI_RESET}");
+            }
+            (_, Some(answer)) => println!(
+                "{label}: {ANSI_BOLD}{answer}{ANSI_RESET} {ANSI_ITALIC}({time}){ANSI_RESET}"
+            ),
+            (Status::Solved, None) => println!("{label}: {ANSI_ITALIC}{time}{ANSI_RESET}"),
+            (_, None) => println!("{label}: ✖"),
+        }
+    }
+}
@@ -2,13 +2,19 @@ use std::error::Error;
 use std::fmt::Display;
 use std::str::FromStr;
 
-#[cfg(feature = "today")]
-use chrono::{Datelike, FixedOffset, Utc};
+use crate::template::Year;
 
 #[cfg(feature = "today")]
-const SERVER_UTC_OFFSET: i32 = -5;
+use crate::template::year::server_now;
+#[cfg(feature = "today")]
+use chrono::Datelike;
+
+/// The number of days of the longest event.
+/// Use [`Year::days`] for the length of a specific event.
+pub const MAX_DAY: u8 = 25;
 
 /// A valid day number of advent (i.e. an integer in range 1 to 25).
+/// Whether a day is part of a specific event depends on the event's length, see [`Year::days`].
 ///
 /// # Display
 /// This value displays as a two digit number.
@@ -25,7 +31,7 @@ impl Day {
     /// Creates a [`Day`] from the provided value if it's in the valid range,
     /// returns [`None`] otherwise.
     pub fn new(day: u8) -> Option<Self> {
-        if day == 0 || day > 25 {
+        if day == 0 || day > MAX_DAY {
             return None;
         }
         Some(Self(day))
@@ -38,19 +44,21 @@ impl Day {
     }
 
     /// Converts the [`Day`] into an [`u8`].
-    pub fn into_inner(self) -> u8 {
+    pub const fn into_inner(self) -> u8 {
         self.0
     }
 }
 
 #[cfg(feature = "today")]
 impl Day {
-    /// Returns the current day if it's between the 1st and the 25th of december, `None` otherwise.
+    /// Returns the current day if an event is running, i.e. if it's december and the day is part of this year's event.
+    /// Returns `None` otherwise.
     pub fn today() -> Option<Self> {
-        let offset = FixedOffset::east_opt(SERVER_UTC_OFFSET * 3600)?;
-        let today = Utc::now().with_timezone(&offset);
-        if today.month() == 12 && today.day() <= 25 {
-            Self::new(u8::try_from(today.day()).ok()?)
+        let today = server_now()?;
+        let year = Year::new(u16::try_from(today.year()).ok()?)?;
+        let day = Self::new(u8::try_from(today.day()).ok()?)?;
+        if today.month() == 12 && year.has_day(day) {
+            Some(day)
         } else {
             None
         }
@@ -100,20 +108,23 @@ impl Display for DayFromStrError {
 
 /* -------------------------------------------------------------------------- */
 
-/// An iterator that yields every day of advent from the 1st to the 25th.
-pub fn all_days() -> AllDays {
-    AllDays::new()
+/// An iterator that yields every day of the event of `year`, from the 1st to its last day.
+pub fn all_days(year: Year) -> AllDays {
+    AllDays::new(year)
 }
 
-/// An iterator that yields every day of advent from the 1st to the 25th.
+/// An iterator that yields every day of the event of a year, from the 1st to its last day.
 pub struct AllDays {
     current: u8,
+    last: u8,
 }
 
 impl AllDays {
-    #[allow(clippy::new_without_default)]
-    pub fn new() -> Self {
-        Self { current: 1 }
+    pub fn new(year: Year) -> Self {
+        Self {
+            current: 1,
+            last: year.days(),
+        }
     }
 }
 
@@ -121,10 +132,10 @@ impl Iterator for AllDays {
     type Item = Day;
 
     fn next(&mut self) -> Option<Self::Item> {
-        if self.current > 25 {
+        if self.current > self.last {
             return None;
         }
-        // NOTE: the iterator starts at 1 and we have verified that the value is not above 25.
+        // NOTE: the iterator starts at 1 and the length of an event is never above 25.
         let day = Day(self.current);
         self.current += 1;
 
@@ -155,10 +166,11 @@ macro_rules! day {
 #[cfg(feature = "test_lib")]
 mod tests {
     use super::{all_days, Day};
+    use crate::year;
 
     #[test]
     fn all_days_iterator() {
-        let mut iter = all_days();
+        let mut iter = all_days(year!(2024));
 
         assert_eq!(iter.next(), Some(Day(1)));
         assert_eq!(iter.next(), Some(Day(2)));
@@ -187,6 +199,13 @@ mod tests {
         assert_eq!(iter.next(), Some(Day(25)));
         assert_eq!(iter.next(), None);
     }
+
+    #[test]
+    fn all_days_iterator_for_short_events() {
+        let days: Vec<Day> = all_days(year!(2025)).collect();
+        assert_eq!(days.len(), 12);
+        assert_eq!(days.last(), Some(&Day(12)));
+    }
 }
 
 /* -------------------------------------------------------------------------- */
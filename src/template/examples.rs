@@ -0,0 +1,193 @@
+/// Extracts examples and their expected answers from a downloaded puzzle description.
+use crate::template::aoc_cli::html;
+
+/// A `<pre>` block of the puzzle description.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CodeBlock {
+    /// The part whose description contains the block.
+    pub part: u8,
+    pub text: String,
+}
+
+/// The code blocks and highlighted answers of a puzzle description.
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct PuzzleExamples {
+    pub blocks: Vec<CodeBlock>,
+    /// The last highlighted value of each part's description, which usually is the answer for the example.
+    pub answers: [Option<String>; 2],
+}
+
+impl PuzzleExamples {
+    /// Parses a description as stored by `cargo download`, i.e. one `<article>` per part.
+    pub fn parse(description: &str) -> Self {
+        let mut examples = Self::default();
+
+        for (index, article) in html::elements(description, "article")
+            .iter()
+            .take(2)
+            .enumerate()
+        {
+            #[allow(clippy::cast_possible_truncation)]
+            let part = index as u8 + 1;
+
+            examples
+                .blocks
+                .extend(html::elements(article, "pre").iter().map(|pre| CodeBlock {
+                    part,
+                    text: html::strip_tags(pre),
+                }));
+
+            examples.answers[index] = find_answer(article);
+        }
+
+        examples
+    }
+
+    /// Returns the index of the block that most likely is the example of `part`,
+    /// i.e. the first block of the part's description that spans multiple lines.
+    pub fn select(&self, part: u8) -> Option<usize> {
+        self.blocks
+            .iter()
+            .position(|block| block.part == part && block.text.trim().contains('\n'))
+    }
+
+    pub fn answer(&self, part: u8) -> Option<&str> {
+        self.answers
+            .get(usize::from(part).checked_sub(1)?)?
+            .as_deref()
+    }
+}
+
+/// Finds the last highlighted value, which is either `<code><em>x</em></code>` or `<em><code>x</code></em>`.
+fn find_answer(article: &str) -> Option<String> {
+    [
+        ("<code><em>", "</em></code>"),
+        ("<em><code>", "</code></em>"),
+    ]
+    .iter()
+    .filter_map(|(open, close)| {
+        let start = article.rfind(open)? + open.len();
+        let end = start + article[start..].find(close)?;
+        Some((start, &article[start..end]))
+    })
+    .max_by_key(|(start, _)| *start)
+    .map(|(_, value)| html::strip_tags(value).trim().to_string())
+    .filter(|value| !value.is_empty() && !value.contains('\n'))
+}
+
+/* -------------------------------------------------------------------------- */
+
+/// Replaces the placeholder assertion in the test of `part` of a scaffolded solution with `answer`.
+/// Returns [`None`] if the answer is not numeric or the test no longer contains the placeholder.
+pub fn prefill_answer(source: &str, part: u8, answer: &str) -> Option<String> {
+    answer.parse::<i128>().ok()?;
+    edit_test(
+        source,
+        part,
+        "assert_eq!(result, None);",
+        &format!("assert_eq!(result, Some({answer}));"),
+    )
+}
+
+/// Makes the test of `part` read the numbered example file `file`, e.g. `01-2.txt`.
+/// Returns [`None`] if the test does not read the shared example file.
+pub fn use_part_example(source: &str, part: u8, file: usize) -> Option<String> {
+    edit_test(
+        source,
+        part,
+        "read_file(\"examples\", PUZZLE)",
+        &format!("read_file_part(\"examples\", PUZZLE, {file})"),
+    )
+}
+
+fn edit_test(source: &str, part: u8, from: &str, to: &str) -> Option<String> {
+    let name = match part {
+        1 => "fn test_part_one()",
+        2 => "fn test_part_two()",
+        _ => return None,
+    };
+
+    let start = source.find(name)?;
+    let end = start + source[start..].find("\n    }")?;
+    let offset = start + source[start..end].find(from)?;
+
+    Some(format!(
+        "{}{to}{}",
+        &source[..offset],
+        &source[offset + from.len()..]
+    ))
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::{prefill_answer, use_part_example, CodeBlock, PuzzleExamples};
+
+    const DESCRIPTION: &str = r#"<article class="day-desc"><h2>--- Day 1: Test ---</h2>
+<p>For example:</p>
+<pre><code>3   4
+4   3
+</code></pre>
+<p>Pairs like <code>3</code> are compared. In this example, the total is <code><em>11</em></code>.</p>
+</article>
+
+<article class="day-desc"><h2 id="part2">--- Part Two ---</h2>
+<p>Now with &lt;arrows&gt;:</p>
+<pre><code><em>1</em>
+-&gt;2
+</code></pre>
+<p>So, the score is <em><code>31</code></em>.</p>
+</article>"#;
+
+    #[test]
+    fn extracts_code_blocks_and_answers() {
+        let examples = PuzzleExamples::parse(DESCRIPTION);
+
+        assert_eq!(
+            examples.blocks,
+            vec![
+                CodeBlock {
+                    part: 1,
+                    text: "3   4\n4   3\n".into()
+                },
+                CodeBlock {
+                    part: 2,
+                    text: "1\n->2\n".into()
+                },
+            ]
+        );
+        assert_eq!(examples.answer(1), Some("11"));
+        assert_eq!(examples.answer(2), Some("31"));
+        assert_eq!(examples.select(1), Some(0));
+        assert_eq!(examples.select(2), Some(1));
+    }
+
+    #[test]
+    fn handles_descriptions_of_unsolved_part_one() {
+        let examples = PuzzleExamples::parse(DESCRIPTION.split("\n\n").next().unwrap());
+        assert_eq!(examples.blocks.len(), 1);
+        assert_eq!(examples.answer(2), None);
+        assert_eq!(examples.select(2), None);
+    }
+
+    #[test]
+    fn prefills_scaffolded_tests() {
+        let source = include_str!(concat!(env!("CARGO_MANIFEST_DIR"), "/src/template.txt"));
+
+        let source = prefill_answer(source, 2, "31").unwrap();
+        let source = use_part_example(&source, 2, 3).unwrap();
+
+        assert!(source.contains("assert_eq!(result, Some(31));"));
+        assert!(source.contains(
+            "part_two(&advent_of_code::template::read_file_part(\"examples\", PUZZLE, 3))"
+        ));
+        assert!(
+            source.contains("part_one(&advent_of_code::template::read_file(\"examples\", PUZZLE))")
+        );
+        assert_eq!(source.matches("assert_eq!(result, None);").count(), 1);
+
+        assert_eq!(prefill_answer(&source, 2, "7"), None);
+        assert_eq!(prefill_answer(&source, 1, "abc"), None);
+    }
+}
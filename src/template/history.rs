@@ -0,0 +1,409 @@
+/// Module that keeps an append-only history of stored benchmark runs.
+use std::{
+    collections::HashMap,
+    fs,
+    io::{Error, ErrorKind},
+    path::Path,
+    process::Command,
+    str::FromStr,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use tinyjson::JsonValue;
+
+use crate::template::{
+    create_data_file,
+    record::PARSE_PART,
+    stats::format_nanos,
+    timings::{Timing, Timings},
+    Day, Year, ANSI_BOLD, ANSI_GREEN, ANSI_ITALIC, ANSI_RED, ANSI_RESET,
+};
+
+static HISTORY_FILE_NAME: &str = "timings_history.json";
+
+/// Describes the circumstances of a benchmark run.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Environment {
+    /// UTC timestamp in RFC 3339 format, e.g. `2024-12-06T08:30:00Z`.
+    pub timestamp: String,
+    pub commit: Option<String>,
+    pub rustc: Option<String>,
+    pub profile: String,
+    pub cpu: Option<String>,
+}
+
+impl Environment {
+    /// Captures the current environment.
+    pub fn capture(is_release: bool) -> Self {
+        Self {
+            timestamp: current_timestamp(),
+            commit: get_commit(),
+            rustc: command_output("rustc", &["-V"])
+                .map(|s| s.trim_start_matches("rustc ").to_string()),
+            profile: if is_release { "release" } else { "dev" }.into(),
+            cpu: get_cpu(),
+        }
+    }
+}
+
+/// A single benchmark run of a day.
+#[derive(Clone, Debug)]
+pub struct HistoryEntry {
+    pub environment: Environment,
+    pub timing: Timing,
+}
+
+/// Represents all stored benchmark runs.
+/// Can be serialized from / to JSON.
+#[derive(Clone, Debug, Default)]
+pub struct History {
+    pub entries: Vec<HistoryEntry>,
+}
+
+impl History {
+    /// Dehydrate history to a JSON file.
+    pub fn store_file(&self, year: Year) -> Result<(), Error> {
+        let json = JsonValue::from(self);
+        let mut file = create_data_file(year, HISTORY_FILE_NAME)?;
+        json.format_to(&mut file)
+    }
+
+    /// Rehydrate history from a JSON file. If not present, returns an empty history.
+    /// Fails if the file can not be read or parsed, so that it is not overwritten by a new history.
+    pub fn read_from_file(year: Year) -> Result<Self, String> {
+        Self::read_from_path(&year.data_dir().join(HISTORY_FILE_NAME))
+    }
+
+    fn read_from_path(path: &Path) -> Result<Self, String> {
+        match fs::read_to_string(path) {
+            Ok(content) => History::try_from(content)
+                .map_err(|e| format!("could not parse `{}`: {e}", path.display())),
+            Err(e) if e.kind() == ErrorKind::NotFound => Ok(History::default()),
+            Err(e) => Err(format!("could not read `{}`: {e}", path.display())),
+        }
+    }
+
+    /// Appends one entry per timed day.
+    pub fn append(&mut self, environment: &Environment, timings: &Timings) {
+        for timing in &timings.data {
+            self.entries.push(HistoryEntry {
+                environment: environment.clone(),
+                timing: timing.clone(),
+            });
+        }
+    }
+
+    /// Returns all entries of a day in chronological order.
+    pub fn for_day(&self, day: Day) -> Vec<&HistoryEntry> {
+        self.entries
+            .iter()
+            .filter(|e| e.timing.day == day)
+            .collect()
+    }
+}
+
+/// Prints how the performance of a day evolved over time.
+pub fn print_day(history: &History, day: Day) {
+    let entries = history.for_day(day);
+
+    println!("{ANSI_BOLD}Day {day} history{ANSI_RESET}");
+    println!("------");
+
+    if entries.is_empty() {
+        println!("No stored runs. Use `cargo time {day} --store` to record one.");
+        return;
+    }
+
+    let mut previous: Option<f64> = None;
+
+    for entry in entries {
+        let env = &entry.environment;
+        let timing = &entry.timing;
+
+        let parts = [(PARSE_PART, "Parse"), (1, "Part 1"), (2, "Part 2")]
+            .into_iter()
+            .filter_map(|(part, label)| {
+                timing
+                    .part_nanos(part)
+                    .map(|nanos| format!("{label}: {}", format_nanos(nanos)))
+            })
+            .collect::<Vec<_>>()
+            .join(" · ");
+
+        let change = match previous {
+            Some(previous) if previous > 0.0 => {
+                let change = (timing.total_nanos - previous) / previous * 100.0;
+                let color = if change > 0.0 { ANSI_RED } else { ANSI_GREEN };
+                format!(" {color}({change:+.1}%){ANSI_RESET}")
+            }
+            _ => String::new(),
+        };
+        previous = Some(timing.total_nanos);
+
+        println!(
+            "{} {} {}  {parts}  Total: {}{change}",
+            env.timestamp,
+            env.commit.as_deref().unwrap_or("-"),
+            env.profile,
+            format_nanos(timing.total_nanos),
+        );
+        println!(
+            "    {ANSI_ITALIC}rustc {} on {}{ANSI_RESET}",
+            env.rustc.as_deref().unwrap_or("unknown"),
+            env.cpu.as_deref().unwrap_or("unknown cpu")
+        );
+    }
+}
+
+fn command_output(program: &str, args: &[&str]) -> Option<String> {
+    let output = Command::new(program).args(args).output().ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    let s = String::from_utf8(output.stdout).ok()?.trim().to_string();
+    (!s.is_empty()).then_some(s)
+}
+
+fn get_commit() -> Option<String> {
+    let commit = command_output("git", &["rev-parse", "--short", "HEAD"])?;
+    let is_dirty = command_output("git", &["status", "--porcelain", "--untracked-files=no"]);
+
+    Some(if is_dirty.is_some() {
+        format!("{commit}-dirty")
+    } else {
+        commit
+    })
+}
+
+fn get_cpu() -> Option<String> {
+    if cfg!(target_os = "linux") {
+        fs::read_to_string("/proc/cpuinfo")
+            .ok()?
+            .lines()
+            .find(|l| l.starts_with("model name"))
+            .and_then(|l| l.split(':').nth(1))
+            .map(|s| s.trim().to_string())
+    } else if cfg!(target_os = "macos") {
+        command_output("sysctl", &["-n", "machdep.cpu.brand_string"])
+    } else {
+        std::env::var("PROCESSOR_IDENTIFIER").ok()
+    }
+}
+
+/// Returns the current time as an RFC 3339 UTC timestamp.
+pub fn current_timestamp() -> String {
+    let secs = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs());
+    format_timestamp(secs)
+}
+
+/// Formats seconds since the unix epoch as an RFC 3339 UTC timestamp.
+/// see: http://howardhinnant.github.io/date_algorithms.html#civil_from_days
+fn format_timestamp(secs: u64) -> String {
+    #[allow(clippy::cast_possible_wrap)]
+    let days = (secs / 86_400) as i64 + 719_468;
+    let time = secs % 86_400;
+
+    let era = days.div_euclid(146_097);
+    let doe = days.rem_euclid(146_097);
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+
+    format!(
+        "{year:04}-{month:02}-{day:02}T{:02}:{:02}:{:02}Z",
+        time / 3600,
+        time % 3600 / 60,
+        time % 60
+    )
+}
+
+/* -------------------------------------------------------------------------- */
+
+impl From<&History> for JsonValue {
+    fn from(value: &History) -> Self {
+        let mut map: HashMap<String, JsonValue> = HashMap::new();
+
+        map.insert(
+            "entries".into(),
+            JsonValue::Array(value.entries.iter().map(JsonValue::from).collect()),
+        );
+
+        JsonValue::Object(map)
+    }
+}
+
+impl TryFrom<String> for History {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        let json = JsonValue::from_str(&value).or(Err("not valid JSON file."))?;
+
+        let json_entries = json
+            .get::<HashMap<String, JsonValue>>()
+            .ok_or("expected JSON document to be an object.")?
+            .get("entries")
+            .ok_or("expected JSON document to have key `entries`.")?
+            .get::<Vec<JsonValue>>()
+            .ok_or("expected `json.entries` to be an array.")?;
+
+        Ok(History {
+            entries: json_entries
+                .iter()
+                .map(HistoryEntry::try_from)
+                .collect::<Result<_, _>>()?,
+        })
+    }
+}
+
+impl From<&HistoryEntry> for JsonValue {
+    fn from(value: &HistoryEntry) -> Self {
+        let mut map: HashMap<String, JsonValue> = HashMap::new();
+        let env = &value.environment;
+
+        let optional = |x: &Option<String>| match x {
+            Some(x) => JsonValue::String(x.clone()),
+            None => JsonValue::Null,
+        };
+
+        map.insert("timestamp".into(), JsonValue::String(env.timestamp.clone()));
+        map.insert("commit".into(), optional(&env.commit));
+        map.insert("rustc".into(), optional(&env.rustc));
+        map.insert("profile".into(), JsonValue::String(env.profile.clone()));
+        map.insert("cpu".into(), optional(&env.cpu));
+        map.insert("timing".into(), JsonValue::from(&value.timing));
+
+        JsonValue::Object(map)
+    }
+}
+
+impl TryFrom<&JsonValue> for HistoryEntry {
+    type Error = String;
+
+    fn try_from(value: &JsonValue) -> Result<Self, Self::Error> {
+        let json = value
+            .get::<HashMap<String, JsonValue>>()
+            .ok_or("Expected history entry to be a JSON object.")?;
+
+        let string = |key: &str| {
+            json.get(key)
+                .and_then(|v| v.get::<String>())
+                .cloned()
+                .ok_or(format!("Expected entry.{key} to be a string."))
+        };
+
+        let optional = |key: &str| {
+            json.get(key)
+                .map(|v| if v.is_null() { None } else { v.get::<String>() })
+                .ok_or(format!("Expected entry.{key} to be null or string."))
+                .map(|v| v.cloned())
+        };
+
+        let timing = json
+            .get("timing")
+            .ok_or("Expected entry.timing to be present.")
+            .map(Timing::try_from)??;
+
+        Ok(HistoryEntry {
+            environment: Environment {
+                timestamp: string("timestamp")?,
+                commit: optional("commit")?,
+                rustc: optional("rustc")?,
+                profile: string("profile")?,
+                cpu: optional("cpu")?,
+            },
+            timing,
+        })
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::{format_timestamp, Environment, History};
+    use crate::{
+        day,
+        template::timings::{Timing, Timings},
+    };
+    use std::{env, fs};
+    use tinyjson::JsonValue;
+
+    fn get_mock_environment(commit: &str) -> Environment {
+        Environment {
+            timestamp: "2024-12-06T08:30:00Z".into(),
+            commit: Some(commit.into()),
+            rustc: Some("1.83.0 (90b35a623 2024-11-26)".into()),
+            profile: "release".into(),
+            cpu: None,
+        }
+    }
+
+    fn get_mock_timings(total_nanos: f64) -> Timings {
+        Timings {
+            data: vec![Timing {
+                part_1: Some("1.0ms".into()),
+                total_nanos,
+                ..Timing::new(day!(6))
+            }],
+        }
+    }
+
+    #[test]
+    fn formats_timestamps() {
+        assert_eq!(format_timestamp(0), "1970-01-01T00:00:00Z");
+        assert_eq!(format_timestamp(1_733_473_800), "2024-12-06T08:30:00Z");
+        assert_eq!(format_timestamp(951_782_400), "2000-02-29T00:00:00Z");
+    }
+
+    #[test]
+    fn appends_entries() {
+        let mut history = History::default();
+        history.append(&get_mock_environment("abc1234"), &get_mock_timings(1e6));
+        history.append(&get_mock_environment("def5678"), &get_mock_timings(2e6));
+
+        let entries = history.for_day(day!(6));
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].environment.commit.as_deref(), Some("abc1234"));
+        assert_eq!(entries[1].timing.total_nanos, 2e6);
+        assert!(history.for_day(day!(7)).is_empty());
+    }
+
+    #[test]
+    fn roundtrips_history() {
+        let mut history = History::default();
+        history.append(&get_mock_environment("abc1234"), &get_mock_timings(1e6));
+
+        let json = JsonValue::from(&history).stringify().unwrap();
+        let parsed = History::try_from(json).unwrap();
+
+        assert_eq!(parsed.entries.len(), 1);
+        assert_eq!(
+            parsed.entries[0].environment,
+            get_mock_environment("abc1234")
+        );
+        assert_eq!(parsed.entries[0].timing.part_1, Some("1.0ms".into()));
+    }
+
+    #[test]
+    #[should_panic]
+    fn panics_for_malformed_history() {
+        let json = r#"{ "entries": [{ "timestamp": "2024-12-06T08:30:00Z" }] }"#.to_string();
+        History::try_from(json).unwrap();
+    }
+
+    #[test]
+    fn refuses_to_read_corrupt_files() {
+        let path = env::temp_dir().join(format!("aoc-history-{}.json", std::process::id()));
+        assert_eq!(History::read_from_path(&path).unwrap().entries.len(), 0);
+
+        fs::write(&path, "{ \"entries\": [").unwrap();
+        let error = History::read_from_path(&path).unwrap_err();
+        let _ = fs::remove_file(&path);
+        assert!(error.starts_with("could not parse"));
+    }
+}
@@ -0,0 +1,230 @@
+/// Heap usage of solution parts, measured by a counting global allocator.
+/// The allocator is installed by `solution!` when the `memory` feature is enabled.
+use std::{
+    alloc::{GlobalAlloc, Layout, System},
+    collections::HashMap,
+    sync::atomic::{AtomicBool, AtomicU64, Ordering},
+};
+
+use tinyjson::JsonValue;
+
+#[cfg(all(feature = "memory", feature = "dhat-heap"))]
+compile_error!(
+    "the `memory` and `dhat-heap` features can not be combined, as both define a global allocator."
+);
+
+static IS_INSTALLED: AtomicBool = AtomicBool::new(false);
+static CURRENT: AtomicU64 = AtomicU64::new(0);
+static PEAK: AtomicU64 = AtomicU64::new(0);
+static TOTAL: AtomicU64 = AtomicU64::new(0);
+static COUNT: AtomicU64 = AtomicU64::new(0);
+
+/// A global allocator that delegates to the system allocator and counts the allocated bytes.
+pub struct CountingAlloc;
+
+impl CountingAlloc {
+    fn on_alloc(size: usize) {
+        let size = size as u64;
+        let current = CURRENT.fetch_add(size, Ordering::Relaxed) + size;
+        PEAK.fetch_max(current, Ordering::Relaxed);
+        TOTAL.fetch_add(size, Ordering::Relaxed);
+        COUNT.fetch_add(1, Ordering::Relaxed);
+        IS_INSTALLED.store(true, Ordering::Relaxed);
+    }
+
+    fn on_dealloc(size: usize) {
+        CURRENT.fetch_sub(size as u64, Ordering::Relaxed);
+    }
+}
+
+// SAFETY: all calls are forwarded to the system allocator unchanged.
+unsafe impl GlobalAlloc for CountingAlloc {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let ptr = System.alloc(layout);
+        if !ptr.is_null() {
+            Self::on_alloc(layout.size());
+        }
+        ptr
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        let ptr = System.alloc_zeroed(layout);
+        if !ptr.is_null() {
+            Self::on_alloc(layout.size());
+        }
+        ptr
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout);
+        Self::on_dealloc(layout.size());
+    }
+
+    /// A reallocation counts as a new allocation of `new_size` bytes, as e.g. a growing `Vec` would need it.
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        let new_ptr = System.realloc(ptr, layout, new_size);
+        if !new_ptr.is_null() {
+            Self::on_alloc(new_size);
+            Self::on_dealloc(layout.size());
+        }
+        new_ptr
+    }
+}
+
+/// Heap usage of a single run of a part.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct MemoryStats {
+    /// Maximum number of bytes that were allocated at the same time, relative to the start of the run.
+    pub peak_bytes: u64,
+    /// Sum of the sizes of all allocations.
+    pub total_bytes: u64,
+    pub allocations: u64,
+}
+
+impl MemoryStats {
+    /// Formats the stats, e.g. `peak 1.2 MiB · 3.4 MiB in 567 allocations`.
+    pub fn format(&self) -> String {
+        format!(
+            "peak {} · {} in {} allocation{}",
+            format_bytes(self.peak_bytes),
+            format_bytes(self.total_bytes),
+            self.allocations,
+            if self.allocations == 1 { "" } else { "s" }
+        )
+    }
+}
+
+/// Runs `func` and measures its heap usage.
+/// Returns no stats if the counting allocator is not installed, i.e. the `memory` feature is disabled.
+pub fn measure<T>(func: impl FnOnce() -> T) -> (T, Option<MemoryStats>) {
+    let base = CURRENT.load(Ordering::Relaxed);
+    PEAK.store(base, Ordering::Relaxed);
+    let total = TOTAL.load(Ordering::Relaxed);
+    let count = COUNT.load(Ordering::Relaxed);
+
+    let result = func();
+
+    if !IS_INSTALLED.load(Ordering::Relaxed) {
+        return (result, None);
+    }
+
+    let stats = MemoryStats {
+        peak_bytes: PEAK.load(Ordering::Relaxed).saturating_sub(base),
+        total_bytes: TOTAL.load(Ordering::Relaxed) - total,
+        allocations: COUNT.load(Ordering::Relaxed) - count,
+    };
+    (result, Some(stats))
+}
+
+/// Formats a number of bytes with binary prefixes, e.g. `512 B` or `1.2 MiB`.
+#[allow(clippy::cast_precision_loss)]
+pub fn format_bytes(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["KiB", "MiB", "GiB", "TiB"];
+
+    if bytes < 1024 {
+        return format!("{bytes} B");
+    }
+
+    let mut value = bytes as f64 / 1024.0;
+    let mut unit = 0;
+    while value >= 1024.0 && unit < UNITS.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+    format!("{value:.1} {}", UNITS[unit])
+}
+
+/* -------------------------------------------------------------------------- */
+
+impl From<&MemoryStats> for JsonValue {
+    #[allow(clippy::cast_precision_loss)]
+    fn from(value: &MemoryStats) -> Self {
+        let mut map: HashMap<String, JsonValue> = HashMap::new();
+
+        map.insert(
+            "peak_bytes".into(),
+            JsonValue::Number(value.peak_bytes as f64),
+        );
+        map.insert(
+            "total_bytes".into(),
+            JsonValue::Number(value.total_bytes as f64),
+        );
+        map.insert(
+            "allocations".into(),
+            JsonValue::Number(value.allocations as f64),
+        );
+
+        JsonValue::Object(map)
+    }
+}
+
+impl TryFrom<&JsonValue> for MemoryStats {
+    type Error = String;
+
+    fn try_from(value: &JsonValue) -> Result<Self, Self::Error> {
+        let json = value
+            .get::<HashMap<String, JsonValue>>()
+            .ok_or("Expected memory to be a JSON object.")?;
+
+        let number = |key: &str| {
+            json.get(key)
+                .and_then(|v| v.get::<f64>().copied())
+                .ok_or(format!("Expected memory.{key} to be a number."))
+        };
+
+        #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+        Ok(MemoryStats {
+            peak_bytes: number("peak_bytes")? as u64,
+            total_bytes: number("total_bytes")? as u64,
+            allocations: number("allocations")? as u64,
+        })
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::{format_bytes, measure, CountingAlloc, MemoryStats};
+    use std::alloc::{GlobalAlloc, Layout};
+    use std::str::FromStr;
+    use tinyjson::JsonValue;
+
+    #[test]
+    fn formats_bytes() {
+        assert_eq!(format_bytes(512), "512 B");
+        assert_eq!(format_bytes(1536), "1.5 KiB");
+        assert_eq!(format_bytes(3 * 1024 * 1024), "3.0 MiB");
+    }
+
+    #[test]
+    fn counts_allocations() {
+        // The test binary uses the system allocator, so the counting allocator is called directly.
+        let layout = Layout::array::<u8>(1000).unwrap();
+        let (_, stats) = measure(|| unsafe {
+            let a = CountingAlloc.alloc(layout);
+            let a = CountingAlloc.realloc(a, layout, 3000);
+            CountingAlloc.dealloc(a, Layout::array::<u8>(3000).unwrap());
+            let b = CountingAlloc.alloc(layout);
+            CountingAlloc.dealloc(b, layout);
+        });
+
+        let stats = stats.unwrap();
+        assert_eq!(stats.allocations, 3);
+        assert_eq!(stats.total_bytes, 5000);
+        assert!(stats.peak_bytes >= 3000);
+        assert!(stats.format().ends_with("· 4.9 KiB in 3 allocations"));
+    }
+
+    #[test]
+    fn roundtrips_memory_stats() {
+        let stats = MemoryStats {
+            peak_bytes: 1024,
+            total_bytes: 4096,
+            allocations: 12,
+        };
+        let json = JsonValue::from(&stats).stringify().unwrap();
+        let parsed = MemoryStats::try_from(&JsonValue::from_str(&json).unwrap()).unwrap();
+        assert_eq!(parsed, stats);
+    }
+}
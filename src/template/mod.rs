@@ -1,44 +1,93 @@
-use std::{env, fs};
+use std::{env, fs, io};
 
 pub mod aoc_cli;
 pub mod commands;
+pub mod memory;
+pub mod registry;
 pub mod runner;
 
+pub use answer::*;
 pub use day::*;
+pub use parse_error::*;
+pub use part_result::*;
+pub use progress::*;
+pub use puzzle::*;
+pub use year::*;
 
+mod answer;
+mod answers;
 mod day;
+mod examples;
+mod history;
+mod parse_error;
+mod part_result;
+mod perf;
+mod progress;
+mod puzzle;
 mod readme_benchmarks;
+mod record;
+mod regression;
 mod run_multi;
+mod stats;
+mod submissions;
 mod timings;
+mod watch;
+mod year;
 
 pub const ANSI_ITALIC: &str = "\x1b[3m";
 pub const ANSI_BOLD: &str = "\x1b[1m";
 pub const ANSI_RESET: &str = "\x1b[0m";
+pub const ANSI_RED: &str = "\x1b[31m";
+pub const ANSI_GREEN: &str = "\x1b[32m";
 
 /// Helper function that reads a text file to a string.
 #[must_use]
-pub fn read_file(folder: &str, day: Day) -> String {
+pub fn read_file(folder: &str, puzzle: Puzzle) -> String {
     let cwd = env::current_dir().unwrap();
-    let filepath = cwd.join("data").join(folder).join(format!("{day}.txt"));
+    let filepath = cwd.join(puzzle.data_path(folder, "txt"));
     let f = fs::read_to_string(filepath);
     f.expect("could not open input file")
 }
 
 /// Helper function that reads a text file to string, appending a part suffix. E.g. like `01-2.txt`.
 #[must_use]
-pub fn read_file_part(folder: &str, day: Day, part: u8) -> String {
+pub fn read_file_part(folder: &str, puzzle: Puzzle, part: u8) -> String {
     let cwd = env::current_dir().unwrap();
     let filepath = cwd
-        .join("data")
+        .join(puzzle.year.data_dir())
         .join(folder)
-        .join(format!("{day}-{part}.txt"));
+        .join(format!("{}-{part}.txt", puzzle.day));
     let f = fs::read_to_string(filepath);
     f.expect("could not open input file")
 }
 
-/// Creates the constant `DAY` and sets up the input and runner for each part.
+/// Helper function that reads an example file of a puzzle by its file name, e.g. `01-2.txt`.
+#[must_use]
+pub fn read_example(puzzle: Puzzle, file_name: &str) -> String {
+    let cwd = env::current_dir().unwrap();
+    let filepath = cwd
+        .join(puzzle.year.data_dir())
+        .join("examples")
+        .join(file_name);
+    fs::read_to_string(&filepath)
+        .unwrap_or_else(|e| panic!("could not open example file `{file_name}`: {e}"))
+}
+
+/// Creates or truncates a file in the data directory of `year`, creating the directory if necessary.
+fn create_data_file(year: Year, name: &str) -> io::Result<fs::File> {
+    let dir = year.data_dir();
+    fs::create_dir_all(&dir)?;
+    fs::File::create(dir.join(name))
+}
+
+/// Creates the constants `YEAR`, `DAY` and `PUZZLE` and sets up the input and runner for each part.
+/// The year is taken from the name of the binary, e.g. `src/bin/2024-01.rs`.
 ///
 /// The optional, second parameter (1 or 2) allows you to only run a single part of the solution.
+///
+/// Passing `parse` (e.g. `solution!(6, parse)` or `solution!(6, parse, 1)`) makes the runner call
+/// a `parse(input: &str)` function once and hand a reference to its output to both parts.
+/// The parse step is timed and reported separately.
 #[macro_export]
 macro_rules! solution {
     ($day:expr) => {
@@ -50,19 +99,139 @@ macro_rules! solution {
     ($day:expr, 2) => {
         $crate::solution!(@impl $day, [part_two, 2]);
     };
+    ($day:expr, parse) => {
+        $crate::solution!(@impl_parse $day, parse, [part_one, 1] [part_two, 2]);
+    };
+    ($day:expr, parse, 1) => {
+        $crate::solution!(@impl_parse $day, parse, [part_one, 1]);
+    };
+    ($day:expr, parse, 2) => {
+        $crate::solution!(@impl_parse $day, parse, [part_two, 2]);
+    };
 
     (@impl $day:expr, $( [$func:expr, $part:expr] )*) => {
+        $crate::solution!(@setup $day);
+
+        pub fn main() {
+            use $crate::template::runner::*;
+            let input = $crate::template::read_file("inputs", PUZZLE);
+            $( run_part($func, &input, PUZZLE, $part); )*
+        }
+    };
+
+    (@impl_parse $day:expr, $parse:expr, $( [$func:expr, $part:expr] )*) => {
+        $crate::solution!(@setup $day);
+
+        pub fn main() {
+            use $crate::template::runner::*;
+            let input = $crate::template::read_file("inputs", PUZZLE);
+            let parsed = run_parse($parse, &input, PUZZLE);
+            $( run_part($func, &parsed, PUZZLE, $part); )*
+        }
+    };
+
+    (@setup $day:expr) => {
+        /// The current year.
+        const YEAR: $crate::template::Year =
+            $crate::template::Year::__from_module_path(module_path!());
         /// The current day.
         const DAY: $crate::template::Day = $crate::day!($day);
+        /// The current puzzle.
+        const PUZZLE: $crate::template::Puzzle = $crate::template::Puzzle::new(YEAR, DAY);
+
+        const _ASSERT_EVENT_LENGTH: () = assert!(
+            YEAR.has_day(DAY),
+            "the day is not part of this year's event"
+        );
 
         #[cfg(feature = "dhat-heap")]
         #[global_allocator]
         static ALLOC: dhat::Alloc = dhat::Alloc;
 
-        fn main() {
-            use $crate::template::runner::*;
-            let input = $crate::template::read_file("inputs", DAY);
-            $( run_part($func, &input, DAY, $part); )*
+        // with the `registry` feature, the main binary installs the allocator for all solutions.
+        #[cfg(all(feature = "memory", not(feature = "registry")))]
+        #[global_allocator]
+        static ALLOC: $crate::template::memory::CountingAlloc =
+            $crate::template::memory::CountingAlloc;
+    };
+}
+
+/// Generates one test per example file and expected answer of each part.
+/// The tests are named after the part and the position of the example, e.g. `examples::part_two::example_2`.
+/// Expected answers are compared against the displayed answer of the part, which may return an `Option` or a `Result`.
+/// A failing test reports its example file and why the part did not produce the expected answer.
+///
+/// Solutions that pass `parse` to `solution!` pass their parse function as well, which is called on each example first.
+///
+/// ```ignore
+/// advent_of_code::examples! {
+///     part_one: ["01.txt" => 18, "01-2.txt" => 4],
+///     part_two: ["01.txt" => 9],
+/// }
+///
+/// advent_of_code::examples! {
+///     parse: parse,
+///     part_one: ["06.txt" => 41],
+/// }
+/// ```
+#[macro_export]
+macro_rules! examples {
+    (parse: $parse:path, $( $part:ident: [ $( $file:literal => $expected:expr ),* $(,)? ] ),* $(,)?) => {
+        #[cfg(test)]
+        mod examples {
+            use super::*;
+
+            $(
+                $crate::examples!(@part $part, |input: &str| $part(&$parse(input)), $( $file => $expected, )*);
+            )*
+        }
+    };
+
+    ($( $part:ident: [ $( $file:literal => $expected:expr ),* $(,)? ] ),* $(,)?) => {
+        #[cfg(test)]
+        mod examples {
+            use super::*;
+
+            $(
+                $crate::examples!(@part $part, |input: &str| $part(input), $( $file => $expected, )*);
+            )*
         }
     };
+
+    (@part $part:ident, $run:expr, $( $rest:tt )*) => {
+        mod $part {
+            use super::*;
+
+            $crate::examples!(
+                @tests $part, $run,
+                [example_1 example_2 example_3 example_4 example_5 example_6 example_7 example_8
+                 example_9 example_10 example_11 example_12 example_13 example_14 example_15 example_16]
+                $( $rest )*
+            );
+        }
+    };
+
+    (@tests $part:ident, $run:expr, [ $name:ident $( $names:ident )* ] $file:literal => $expected:expr, $( $rest:tt )*) => {
+        #[test]
+        fn $name() {
+            let input = $crate::template::read_example(PUZZLE, $file);
+            let answer = $crate::template::PartResult::into_answer(($run)(&input))
+                .map(|answer| answer.to_string());
+            assert_eq!(
+                answer,
+                Ok($expected.to_string()),
+                "{} failed for example `{}`",
+                stringify!($part),
+                $file
+            );
+        }
+
+        $crate::examples!(@tests $part, $run, [ $( $names )* ] $( $rest )*);
+    };
+
+    (@tests $part:ident, $run:expr, [ $( $names:ident )* ]) => {};
+
+    (@tests $part:ident, $run:expr, [] $( $rest:tt )+) => {
+        compile_error!("`examples!` supports up to 16 examples per part");
+    };
 }
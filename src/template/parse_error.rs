@@ -0,0 +1,127 @@
+use std::error::Error;
+use std::fmt::Display;
+
+/// An error in a puzzle input, located by line and column.
+///
+/// # Display
+/// The error displays with the offending line of the input and a marker below the column.
+///
+/// ```
+/// # use advent_of_code::template::ParseError;
+/// let error = ParseError::at("3   4\n4 x 3", 2, 3, "expected a number");
+/// assert_eq!(error.to_string(), "line 2, column 3: expected a number\n    4 x 3\n      ^");
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    /// The line of the error, starting at 1.
+    pub line: usize,
+    /// The column of the error in characters, starting at 1.
+    pub column: usize,
+    pub message: String,
+    source_line: String,
+}
+
+impl ParseError {
+    /// Creates an error at `line` and `column` of `input`, both starting at 1.
+    pub fn at(input: &str, line: usize, column: usize, message: impl Into<String>) -> Self {
+        Self {
+            line,
+            column,
+            message: message.into(),
+            source_line: input
+                .lines()
+                .nth(line.saturating_sub(1))
+                .unwrap_or_default()
+                .to_string(),
+        }
+    }
+
+    /// Creates an error at the byte `offset` of `input`.
+    pub fn at_offset(input: &str, offset: usize, message: impl Into<String>) -> Self {
+        let mut offset = offset.min(input.len());
+        while !input.is_char_boundary(offset) {
+            offset -= 1;
+        }
+        let before = &input[..offset];
+        let line_start = before.rfind('\n').map_or(0, |i| i + 1);
+
+        Self::at(
+            input,
+            before.matches('\n').count() + 1,
+            before[line_start..].chars().count() + 1,
+            message,
+        )
+    }
+
+    /// Creates an error at the start of `part`, which must be a slice of `input`, e.g. a token returned by `split`.
+    /// Falls back to the start of the input if `part` is not a slice of it.
+    pub fn at_slice(input: &str, part: &str, message: impl Into<String>) -> Self {
+        let offset = (part.as_ptr() as usize)
+            .checked_sub(input.as_ptr() as usize)
+            .filter(|offset| *offset <= input.len())
+            .unwrap_or(0);
+        Self::at_offset(input, offset, message)
+    }
+}
+
+impl Error for ParseError {}
+
+impl Display for ParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "line {}, column {}: {}",
+            self.line, self.column, self.message
+        )?;
+
+        if !self.source_line.is_empty() {
+            write!(
+                f,
+                "\n    {}\n    {}^",
+                self.source_line,
+                " ".repeat(self.column.saturating_sub(1))
+            )?;
+        }
+
+        Ok(())
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::ParseError;
+
+    const INPUT: &str = "7 6 4\n1 2 x\n9 7";
+
+    #[test]
+    fn locates_offsets() {
+        let error = ParseError::at_offset(INPUT, 10, "expected a number");
+        assert_eq!((error.line, error.column), (2, 5));
+        assert_eq!(
+            error.to_string(),
+            "line 2, column 5: expected a number\n    1 2 x\n        ^"
+        );
+    }
+
+    #[test]
+    fn locates_slices() {
+        let token = INPUT.split_whitespace().nth(5).unwrap();
+        let error = ParseError::at_slice(INPUT, token, "expected a number");
+        assert_eq!((error.line, error.column), (2, 5));
+
+        let error = ParseError::at_slice(INPUT, "x", "not a slice");
+        assert_eq!((error.line, error.column), (1, 1));
+    }
+
+    #[test]
+    fn handles_positions_past_the_end() {
+        let error = ParseError::at_offset(INPUT, 100, "unexpected end of input");
+        assert_eq!((error.line, error.column), (3, 4));
+        assert_eq!(
+            ParseError::at(INPUT, 9, 1, "missing line").to_string(),
+            "line 9, column 1: missing line"
+        );
+    }
+}
@@ -0,0 +1,91 @@
+/// Return types of solution parts. Parts return either an `Option<T>` or a `Result<T, E>`.
+use std::any::Any;
+use std::fmt::Display;
+
+/// Reason why a part did not produce an answer.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Failure {
+    /// The part returned `None`.
+    Unsolved,
+    /// The part returned an error.
+    Error(String),
+    /// The part panicked.
+    Panic(String),
+}
+
+impl Failure {
+    /// Creates a [`Failure::Panic`] from the payload of a caught panic.
+    pub fn from_panic(payload: &(dyn Any + Send)) -> Self {
+        let message = payload
+            .downcast_ref::<&str>()
+            .map(ToString::to_string)
+            .or_else(|| payload.downcast_ref::<String>().cloned())
+            .unwrap_or_else(|| "unknown panic payload".into());
+        Failure::Panic(message)
+    }
+}
+
+impl Display for Failure {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Failure::Unsolved => Ok(()),
+            Failure::Error(e) => f.write_str(e),
+            Failure::Panic(e) => write!(f, "panicked: {e}"),
+        }
+    }
+}
+
+/// Types that a solution part can return.
+pub trait PartResult {
+    type Answer: Display;
+
+    fn into_answer(self) -> Result<Self::Answer, Failure>;
+}
+
+impl<T: Display> PartResult for Option<T> {
+    type Answer = T;
+
+    fn into_answer(self) -> Result<T, Failure> {
+        self.ok_or(Failure::Unsolved)
+    }
+}
+
+impl<T: Display, E: Display> PartResult for Result<T, E> {
+    type Answer = T;
+
+    fn into_answer(self) -> Result<T, Failure> {
+        self.map_err(|e| Failure::Error(e.to_string()))
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::{Failure, PartResult};
+    use std::panic;
+
+    #[test]
+    fn converts_options() {
+        assert_eq!(Some(42).into_answer(), Ok(42));
+        assert_eq!(None::<u32>.into_answer(), Err(Failure::Unsolved));
+    }
+
+    #[test]
+    fn converts_results() {
+        assert_eq!(Ok::<u32, String>(42).into_answer(), Ok(42));
+        assert_eq!(
+            Err::<u32, _>("no path found").into_answer(),
+            Err(Failure::Error("no path found".into()))
+        );
+    }
+
+    #[test]
+    fn reads_panic_messages() {
+        let payload = panic::catch_unwind(|| panic!("cycle detected at {}", 3)).unwrap_err();
+        assert_eq!(
+            Failure::from_panic(payload.as_ref()).to_string(),
+            "panicked: cycle detected at 3"
+        );
+    }
+}
@@ -0,0 +1,374 @@
+/// Hardware performance counters for benchmarks, read with `perf_event_open` on Linux.
+/// Instruction counts are far more stable than wall-clock times on busy machines.
+use std::{collections::HashMap, fmt::Write};
+
+use tinyjson::JsonValue;
+
+/// Average values of the hardware counters for a single sample of a benchmark.
+/// Counters that are not supported by the machine are `None`.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Counters {
+    pub instructions: Option<f64>,
+    pub cycles: Option<f64>,
+    pub cache_misses: Option<f64>,
+    pub branch_misses: Option<f64>,
+}
+
+impl Counters {
+    /// Formats the counters, e.g. `1.2M instructions · 800.0k cycles (1.50 IPC) · 12 cache misses · 34 branch misses`.
+    pub fn format(&self) -> String {
+        let mut parts = vec![];
+
+        if let Some(instructions) = self.instructions {
+            parts.push(format!("{} instructions", format_count(instructions)));
+        }
+
+        if let Some(cycles) = self.cycles {
+            let mut s = format!("{} cycles", format_count(cycles));
+            if let Some(instructions) = self.instructions.filter(|_| cycles > 0.0) {
+                let _ = write!(s, " ({:.2} IPC)", instructions / cycles);
+            }
+            parts.push(s);
+        }
+
+        if let Some(cache_misses) = self.cache_misses {
+            parts.push(format!("{} cache misses", format_count(cache_misses)));
+        }
+
+        if let Some(branch_misses) = self.branch_misses {
+            parts.push(format!("{} branch misses", format_count(branch_misses)));
+        }
+
+        parts.join(" · ")
+    }
+
+    #[cfg_attr(not(all(feature = "perf", target_os = "linux")), allow(dead_code))]
+    fn per_sample(self, samples: usize) -> Self {
+        #[allow(clippy::cast_precision_loss)]
+        let samples = samples.max(1) as f64;
+        let divide = |x: Option<f64>| x.map(|x| x / samples);
+        Self {
+            instructions: divide(self.instructions),
+            cycles: divide(self.cycles),
+            cache_misses: divide(self.cache_misses),
+            branch_misses: divide(self.branch_misses),
+        }
+    }
+}
+
+/// Runs `samples` iterations of a benchmark with the counters enabled.
+/// `func` returns the number of samples it took, the counters are averaged over them.
+/// Returns no counters if the `perf` feature is disabled or the counters can not be opened.
+pub fn count<T>(func: impl FnOnce() -> (T, usize)) -> (T, Option<Counters>) {
+    #[cfg(all(feature = "perf", target_os = "linux"))]
+    {
+        match sys::CounterGroup::open() {
+            Ok(group) => {
+                group.start();
+                let (result, samples) = func();
+                let counters = group.stop().per_sample(samples);
+                (result, Some(counters))
+            }
+            Err(e) => {
+                report_unavailable(&e);
+                (func().0, None)
+            }
+        }
+    }
+
+    #[cfg(not(all(feature = "perf", target_os = "linux")))]
+    {
+        (func().0, None)
+    }
+}
+
+/// Prints why the counters are unavailable, once per process.
+#[cfg(all(feature = "perf", target_os = "linux"))]
+fn report_unavailable(error: &str) {
+    use crate::template::{ANSI_ITALIC, ANSI_RESET};
+    use std::sync::Once;
+
+    static REPORTED: Once = Once::new();
+    REPORTED.call_once(|| {
+        eprintln!("\r{ANSI_ITALIC}hardware counters are unavailable: {error}{ANSI_RESET}");
+    });
+}
+
+/// Formats a count with a metric suffix, e.g. `12`, `800.0k` or `1.2M`.
+fn format_count(count: f64) -> String {
+    match count {
+        x if x >= 1e9 => format!("{:.1}G", x / 1e9),
+        x if x >= 1e6 => format!("{:.1}M", x / 1e6),
+        x if x >= 1e3 => format!("{:.1}k", x / 1e3),
+        x => format!("{x:.0}"),
+    }
+}
+
+#[cfg(all(feature = "perf", target_os = "linux"))]
+mod sys {
+    use std::{
+        fs::File,
+        io::{self, Read},
+        os::fd::{AsRawFd, FromRawFd},
+    };
+
+    use super::Counters;
+
+    const PERF_TYPE_HARDWARE: u32 = 0;
+    const PERF_COUNT_HW_CPU_CYCLES: u64 = 0;
+    const PERF_COUNT_HW_INSTRUCTIONS: u64 = 1;
+    const PERF_COUNT_HW_CACHE_MISSES: u64 = 3;
+    const PERF_COUNT_HW_BRANCH_MISSES: u64 = 5;
+
+    const PERF_FORMAT_TOTAL_TIME_ENABLED: u64 = 1;
+    const PERF_FORMAT_TOTAL_TIME_RUNNING: u64 = 1 << 1;
+
+    /// Bits of `PerfEventAttr::flags`.
+    const FLAG_DISABLED: u64 = 1;
+    const FLAG_EXCLUDE_KERNEL: u64 = 1 << 5;
+    const FLAG_EXCLUDE_HV: u64 = 1 << 6;
+
+    const PERF_FLAG_FD_CLOEXEC: libc::c_ulong = 1 << 3;
+
+    const PERF_EVENT_IOC_ENABLE: libc::c_ulong = 0x2400;
+    const PERF_EVENT_IOC_DISABLE: libc::c_ulong = 0x2401;
+    const PERF_EVENT_IOC_RESET: libc::c_ulong = 0x2403;
+
+    /// The first version of `struct perf_event_attr`, which every kernel since 2.6.31 accepts.
+    #[repr(C)]
+    #[derive(Default)]
+    struct PerfEventAttr {
+        kind: u32,
+        size: u32,
+        config: u64,
+        sample_period: u64,
+        sample_type: u64,
+        read_format: u64,
+        flags: u64,
+        wakeup_events: u32,
+        bp_type: u32,
+        config1: u64,
+    }
+
+    const _: () = assert!(size_of::<PerfEventAttr>() == 64);
+
+    struct Counter(File);
+
+    impl Counter {
+        /// Opens a counter for user space code of the current thread, on any CPU.
+        fn open(config: u64) -> io::Result<Self> {
+            let attr = PerfEventAttr {
+                kind: PERF_TYPE_HARDWARE,
+                #[allow(clippy::cast_possible_truncation)]
+                size: size_of::<PerfEventAttr>() as u32,
+                config,
+                read_format: PERF_FORMAT_TOTAL_TIME_ENABLED | PERF_FORMAT_TOTAL_TIME_RUNNING,
+                flags: FLAG_DISABLED | FLAG_EXCLUDE_KERNEL | FLAG_EXCLUDE_HV,
+                ..Default::default()
+            };
+
+            // SAFETY: `attr` is a valid `perf_event_attr` that outlives the call.
+            let fd = unsafe {
+                libc::syscall(
+                    libc::SYS_perf_event_open,
+                    &raw const attr,
+                    0,
+                    -1,
+                    -1,
+                    PERF_FLAG_FD_CLOEXEC,
+                )
+            };
+
+            if fd < 0 {
+                return Err(io::Error::last_os_error());
+            }
+
+            #[allow(clippy::cast_possible_truncation)]
+            // SAFETY: the kernel returned a new file descriptor that is owned by nobody else.
+            Ok(Self(unsafe { File::from_raw_fd(fd as i32) }))
+        }
+
+        fn ioctl(&self, request: libc::c_ulong) {
+            // SAFETY: the request is one of the perf ioctls, which take no argument.
+            unsafe { libc::ioctl(self.0.as_raw_fd(), request, 0) };
+        }
+
+        /// Reads the value, scaled up if the kernel had to share the hardware counter with other events.
+        #[allow(clippy::cast_precision_loss)]
+        fn read(&self) -> Option<f64> {
+            let mut buf = [0u8; 24];
+            (&self.0).read_exact(&mut buf).ok()?;
+            let [value, enabled, running] =
+                [0, 8, 16].map(|i| u64::from_ne_bytes(buf[i..i + 8].try_into().unwrap()));
+
+            if running == 0 {
+                return None;
+            }
+            Some(value as f64 * enabled as f64 / running as f64)
+        }
+    }
+
+    pub struct CounterGroup {
+        instructions: Option<Counter>,
+        cycles: Option<Counter>,
+        cache_misses: Option<Counter>,
+        branch_misses: Option<Counter>,
+    }
+
+    impl CounterGroup {
+        /// Opens all counters. Fails if none of them is available, e.g. in a container without perf support.
+        pub fn open() -> Result<Self, String> {
+            let instructions = Counter::open(PERF_COUNT_HW_INSTRUCTIONS);
+            let error = instructions.as_ref().err().map(describe);
+
+            let group = Self {
+                instructions: instructions.ok(),
+                cycles: Counter::open(PERF_COUNT_HW_CPU_CYCLES).ok(),
+                cache_misses: Counter::open(PERF_COUNT_HW_CACHE_MISSES).ok(),
+                branch_misses: Counter::open(PERF_COUNT_HW_BRANCH_MISSES).ok(),
+            };
+
+            if group.counters().all(|c| c.is_none()) {
+                return Err(error.unwrap_or_else(|| "no counter could be opened.".into()));
+            }
+            Ok(group)
+        }
+
+        pub fn start(&self) {
+            for counter in self.counters().flatten() {
+                counter.ioctl(PERF_EVENT_IOC_RESET);
+                counter.ioctl(PERF_EVENT_IOC_ENABLE);
+            }
+        }
+
+        pub fn stop(&self) -> Counters {
+            for counter in self.counters().flatten() {
+                counter.ioctl(PERF_EVENT_IOC_DISABLE);
+            }
+
+            let read = |c: &Option<Counter>| c.as_ref().and_then(Counter::read);
+            Counters {
+                instructions: read(&self.instructions),
+                cycles: read(&self.cycles),
+                cache_misses: read(&self.cache_misses),
+                branch_misses: read(&self.branch_misses),
+            }
+        }
+
+        fn counters(&self) -> impl Iterator<Item = &Option<Counter>> {
+            [
+                &self.instructions,
+                &self.cycles,
+                &self.cache_misses,
+                &self.branch_misses,
+            ]
+            .into_iter()
+        }
+    }
+
+    fn describe(error: &io::Error) -> String {
+        match error.raw_os_error() {
+            Some(libc::EACCES | libc::EPERM) => format!(
+                "{error}. Lower `/proc/sys/kernel/perf_event_paranoid` to 2 or below to allow them."
+            ),
+            Some(libc::ENOENT | libc::ENOSYS | libc::EOPNOTSUPP) => {
+                format!("{error}. The machine does not expose hardware counters, e.g. in a VM or container.")
+            }
+            _ => error.to_string(),
+        }
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+impl From<&Counters> for JsonValue {
+    fn from(value: &Counters) -> Self {
+        let mut map: HashMap<String, JsonValue> = HashMap::new();
+
+        for (key, counter) in [
+            ("instructions", value.instructions),
+            ("cycles", value.cycles),
+            ("cache_misses", value.cache_misses),
+            ("branch_misses", value.branch_misses),
+        ] {
+            if let Some(counter) = counter {
+                map.insert(key.into(), JsonValue::Number(counter));
+            }
+        }
+
+        JsonValue::Object(map)
+    }
+}
+
+impl TryFrom<&JsonValue> for Counters {
+    type Error = String;
+
+    fn try_from(value: &JsonValue) -> Result<Self, Self::Error> {
+        let json = value
+            .get::<HashMap<String, JsonValue>>()
+            .ok_or("Expected counters to be a JSON object.")?;
+
+        let number = |key: &str| match json.get(key) {
+            Some(v) if !v.is_null() => v
+                .get::<f64>()
+                .copied()
+                .map(Some)
+                .ok_or(format!("Expected counters.{key} to be a number.")),
+            _ => Ok(None),
+        };
+
+        Ok(Counters {
+            instructions: number("instructions")?,
+            cycles: number("cycles")?,
+            cache_misses: number("cache_misses")?,
+            branch_misses: number("branch_misses")?,
+        })
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::{count, Counters};
+    use std::str::FromStr;
+    use tinyjson::JsonValue;
+
+    #[test]
+    fn formats_counters() {
+        let counters = Counters {
+            instructions: Some(1_200_000.0),
+            cycles: Some(800_000.0),
+            cache_misses: Some(12.4),
+            branch_misses: None,
+        };
+        assert_eq!(
+            counters.format(),
+            "1.2M instructions · 800.0k cycles (1.50 IPC) · 12 cache misses"
+        );
+        assert_eq!(counters.per_sample(4).instructions, Some(300_000.0));
+    }
+
+    #[test]
+    fn roundtrips_counters() {
+        let counters = Counters {
+            instructions: Some(1000.0),
+            cycles: None,
+            cache_misses: Some(3.0),
+            branch_misses: Some(1.5),
+        };
+        let json = JsonValue::from(&counters).stringify().unwrap();
+        let parsed = Counters::try_from(&JsonValue::from_str(&json).unwrap()).unwrap();
+        assert_eq!(parsed, counters);
+    }
+
+    #[test]
+    fn returns_the_result_without_counters() {
+        // counters are optional, the benchmark runs whether or not they can be read.
+        let (result, counters) = count(|| ((0..1000u64).sum::<u64>(), 1000));
+        assert_eq!(result, 499_500);
+        if let Some(counters) = counters {
+            assert!(counters.instructions.is_none_or(|x| x > 0.0));
+        }
+    }
+}
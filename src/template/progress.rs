@@ -0,0 +1,191 @@
+/// Progress bars for long-running solutions.
+/// They render to stderr, so they never end up in answers or in the output parsed by `run_multi`.
+use std::io::{stderr, IsTerminal, Write};
+use std::time::{Duration, Instant};
+
+use crate::template::record::RECORDS_ARG;
+use crate::template::runner;
+
+/// Minimum time between two redraws, so that hot loops are not slowed down by rendering.
+const REDRAW_INTERVAL: Duration = Duration::from_millis(100);
+const BAR_WIDTH: usize = 30;
+const CLEAR_LINE: &str = "\r\x1b[K";
+
+/// A progress bar for a known amount of work, e.g. candidates to check.
+///
+/// The bar is only drawn if stderr is a terminal. It is a no-op while benchmarking with `--time`
+/// and while the solution is run by `cargo all`, `cargo time` or `cargo check-answers`.
+///
+/// ```no_run
+/// use advent_of_code::template::Progress;
+///
+/// let candidates = vec![1, 2, 3];
+/// let mut progress = Progress::new("Checking candidates", candidates.len());
+/// for candidate in candidates {
+///     progress.inc();
+/// }
+/// ```
+pub struct Progress {
+    label: String,
+    current: usize,
+    total: usize,
+    is_enabled: bool,
+    started: Instant,
+    last_draw: Option<Instant>,
+}
+
+impl Progress {
+    pub fn new(label: &str, total: usize) -> Self {
+        Self {
+            label: label.to_string(),
+            current: 0,
+            total,
+            is_enabled: stderr().is_terminal() && !is_captured(&runner::args()),
+            started: Instant::now(),
+            last_draw: None,
+        }
+    }
+
+    /// Advances the progress by one.
+    pub fn inc(&mut self) {
+        self.set(self.current + 1);
+    }
+
+    /// Advances the progress by `n`.
+    pub fn add(&mut self, n: usize) {
+        self.set(self.current + n);
+    }
+
+    pub fn set(&mut self, current: usize) {
+        self.current = current;
+        if !self.is_enabled {
+            return;
+        }
+
+        let now = Instant::now();
+        if self
+            .last_draw
+            .is_none_or(|last| now - last >= REDRAW_INTERVAL)
+        {
+            self.last_draw = Some(now);
+            let line = render(&self.label, self.current, self.total, now - self.started);
+            let mut stderr = stderr().lock();
+            let _ = write!(stderr, "{CLEAR_LINE}{line}");
+            let _ = stderr.flush();
+        }
+    }
+
+    /// Removes the progress bar. Also happens when the bar is dropped.
+    pub fn finish(&mut self) {
+        if self.is_enabled && self.last_draw.take().is_some() {
+            eprint!("{CLEAR_LINE}");
+        }
+    }
+}
+
+impl Drop for Progress {
+    fn drop(&mut self) {
+        self.finish();
+    }
+}
+
+/// Adds [`progress`](ProgressIterator::progress) to iterators, to show a bar while iterating.
+pub trait ProgressIterator: Iterator + Sized {
+    fn progress(self, label: &str) -> ProgressIter<Self> {
+        let (lower, upper) = self.size_hint();
+        ProgressIter {
+            progress: Progress::new(label, upper.unwrap_or(lower)),
+            iter: self,
+        }
+    }
+}
+
+impl<I: Iterator> ProgressIterator for I {}
+
+pub struct ProgressIter<I> {
+    iter: I,
+    progress: Progress,
+}
+
+impl<I: Iterator> Iterator for ProgressIter<I> {
+    type Item = I::Item;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let item = self.iter.next();
+        match item {
+            Some(_) => self.progress.inc(),
+            None => self.progress.finish(),
+        }
+        item
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+
+/// Whether the output of the solution is captured, i.e. it is benchmarked or run by `run_multi`.
+fn is_captured(args: &[String]) -> bool {
+    args.iter().any(|x| x == "--time" || x == RECORDS_ARG)
+}
+
+#[allow(
+    clippy::cast_possible_truncation,
+    clippy::cast_precision_loss,
+    clippy::cast_sign_loss
+)]
+fn render(label: &str, current: usize, total: usize, elapsed: Duration) -> String {
+    let ratio = if total == 0 {
+        1.0
+    } else {
+        (current as f64 / total as f64).min(1.0)
+    };
+    let filled = (ratio * BAR_WIDTH as f64).round() as usize;
+    format!(
+        "{label} [{}{}] {:>3}% ({current}/{total}, {:.1}s)",
+        "#".repeat(filled),
+        " ".repeat(BAR_WIDTH - filled),
+        (ratio * 100.0).floor() as u8,
+        elapsed.as_secs_f64()
+    )
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::{is_captured, render, ProgressIterator};
+    use std::time::Duration;
+
+    #[test]
+    fn renders_bars() {
+        let line = render("Checking", 45, 100, Duration::from_millis(1500));
+        assert_eq!(
+            line,
+            format!(
+                "Checking [{}{}]  45% (45/100, 1.5s)",
+                "#".repeat(14),
+                " ".repeat(16)
+            )
+        );
+        assert!(render("Checking", 3, 0, Duration::ZERO).contains("100%"));
+    }
+
+    #[test]
+    fn is_silent_when_captured() {
+        let args = |x: &[&str]| x.iter().map(ToString::to_string).collect::<Vec<_>>();
+        assert!(!is_captured(&args(&["2024-06"])));
+        assert!(is_captured(&args(&["2024-06", "--time"])));
+        assert!(is_captured(&args(&[
+            "2024-06",
+            "--records",
+            "target/06.jsonl"
+        ])));
+    }
+
+    #[test]
+    fn passes_items_through() {
+        let items: Vec<u32> = (1..=5).progress("Summing").collect();
+        assert_eq!(items, vec![1, 2, 3, 4, 5]);
+    }
+}
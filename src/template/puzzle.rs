@@ -0,0 +1,108 @@
+use std::error::Error;
+use std::fmt::Display;
+use std::path::PathBuf;
+use std::str::FromStr;
+
+use crate::template::{Day, Year};
+
+/// Identifies a puzzle by its year and day.
+///
+/// # Display
+/// This value displays as `<year>-<day>`, which is also the name of the solution binary.
+///
+/// ```
+/// # use advent_of_code::template::{Day, Puzzle, Year};
+/// let puzzle = Puzzle::new(Year::new(2024).unwrap(), Day::new(8).unwrap());
+/// assert_eq!(puzzle.to_string(), "2024-08")
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Puzzle {
+    pub year: Year,
+    pub day: Day,
+}
+
+impl Puzzle {
+    pub const fn new(year: Year, day: Day) -> Self {
+        Self { year, day }
+    }
+
+    /// Creates a [`Puzzle`] if `day` is part of the event of `year`, returns [`None`] otherwise.
+    pub fn checked(year: Year, day: Day) -> Option<Self> {
+        year.has_day(day).then_some(Self { year, day })
+    }
+
+    /// Returns the path of a data file of this puzzle, e.g. `data/2024/inputs/01.txt`.
+    pub fn data_path(self, folder: &str, extension: &str) -> PathBuf {
+        self.year
+            .data_dir()
+            .join(folder)
+            .join(format!("{}.{extension}", self.day))
+    }
+
+    /// Returns the path of the solution binary, e.g. `src/bin/2024-01.rs`.
+    pub fn bin_path(self) -> PathBuf {
+        PathBuf::from("src").join("bin").join(format!("{self}.rs"))
+    }
+}
+
+impl Display for Puzzle {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}-{}", self.year, self.day)
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+impl FromStr for Puzzle {
+    type Err = PuzzleFromStrError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (year, day) = s.split_once('-').ok_or(PuzzleFromStrError)?;
+        Self::checked(
+            year.parse().map_err(|_| PuzzleFromStrError)?,
+            day.parse().map_err(|_| PuzzleFromStrError)?,
+        )
+        .ok_or(PuzzleFromStrError)
+    }
+}
+
+/// An error which can be returned when parsing a [`Puzzle`].
+#[derive(Debug)]
+pub struct PuzzleFromStrError;
+
+impl Error for PuzzleFromStrError {}
+
+impl Display for PuzzleFromStrError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("expecting a puzzle in the format `<year>-<day>`")
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::Puzzle;
+    use crate::{day, year};
+    use std::path::PathBuf;
+
+    #[test]
+    fn roundtrips_puzzles() {
+        let puzzle = Puzzle::new(year!(2024), day!(1));
+        assert_eq!(puzzle.to_string(), "2024-01");
+        assert_eq!("2024-01".parse::<Puzzle>().ok(), Some(puzzle));
+        assert!("2024".parse::<Puzzle>().is_err());
+        assert!("2024-26".parse::<Puzzle>().is_err());
+        assert!("2025-13".parse::<Puzzle>().is_err());
+    }
+
+    #[test]
+    fn builds_paths() {
+        let puzzle = Puzzle::new(year!(2024), day!(1));
+        assert_eq!(
+            puzzle.data_path("inputs", "txt"),
+            PathBuf::from("data/2024/inputs/01.txt")
+        );
+        assert_eq!(puzzle.bin_path(), PathBuf::from("src/bin/2024-01.rs"));
+    }
+}
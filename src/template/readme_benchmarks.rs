@@ -2,8 +2,10 @@
 /// The approach taken is similar to how `aoc-readme-stars` handles this.
 use std::{fs, io};
 
+use crate::template::memory::{format_bytes, MemoryStats};
+use crate::template::stats::Stats;
 use crate::template::timings::Timings;
-use crate::template::Day;
+use crate::template::{Puzzle, Year};
 
 static MARKER: &str = "<!--- benchmarking table --->";
 
@@ -25,11 +27,6 @@ pub struct TablePosition {
     pos_end: usize,
 }
 
-#[must_use]
-pub fn get_path_for_bin(day: Day) -> String {
-    format!("./src/bin/{day}.rs")
-}
-
 fn locate_table(readme: &str) -> Result<TablePosition, Error> {
     let matches: Vec<_> = readme.match_indices(MARKER).collect();
 
@@ -52,47 +49,112 @@ fn locate_table(readme: &str) -> Result<TablePosition, Error> {
     Ok(TablePosition { pos_start, pos_end })
 }
 
-fn construct_table(prefix: &str, timings: Timings, total_millis: f64) -> String {
-    let header = format!("{prefix} Benchmarks");
+/// Prefers the spread of the stored stats over the plain mean when available.
+/// The peak heap usage is appended if it was measured.
+fn format_part(
+    timing: Option<String>,
+    stats: Option<&Stats>,
+    memory: Option<&MemoryStats>,
+) -> String {
+    let time = match (timing, stats) {
+        (Some(_), Some(stats)) => stats.format_spread(),
+        (Some(timing), None) => timing,
+        (None, _) => "-".into(),
+    };
+
+    match memory {
+        Some(memory) => format!("`{time}` · `{}`", format_bytes(memory.peak_bytes)),
+        None => format!("`{time}`"),
+    }
+}
+
+fn construct_table(prefix: &str, year: Year, timings: Timings) -> Vec<String> {
+    let header = format!("{prefix} Benchmarks {year}");
+    let total_millis = timings.total_millis();
+
+    // only show the parse column if at least one solution has a separate parse step.
+    let has_parse = timings.data.iter().any(|t| t.parse.is_some());
 
-    let mut lines: Vec<String> = vec![
-        MARKER.into(),
-        header,
-        String::new(),
-        "| Day | Part 1 | Part 2 |".into(),
-        "| :---: | :---: | :---:  |".into(),
-    ];
+    let mut lines: Vec<String> = vec![header, String::new()];
+
+    if has_parse {
+        lines.push("| Day | Parse | Part 1 | Part 2 |".into());
+        lines.push("| :---: | :---: | :---: | :---:  |".into());
+    } else {
+        lines.push("| Day | Part 1 | Part 2 |".into());
+        lines.push("| :---: | :---: | :---:  |".into());
+    }
 
     for timing in timings.data {
-        let path = get_path_for_bin(timing.day);
+        let path = Puzzle::new(year, timing.day).bin_path();
+        let parse = if has_parse {
+            format!(
+                " {} |",
+                format_part(
+                    timing.parse,
+                    timing.parse_stats.as_ref(),
+                    timing.parse_memory.as_ref()
+                )
+            )
+        } else {
+            String::new()
+        };
+
         lines.push(format!(
-            "| [Day {}]({}) | `{}` | `{}` |",
+            "| [Day {}](./{}) |{} {} | {} |",
             timing.day.into_inner(),
-            path,
-            timing.part_1.unwrap_or_else(|| "-".into()),
-            timing.part_2.unwrap_or_else(|| "-".into())
+            path.to_string_lossy().replace('\\', "/"),
+            parse,
+            format_part(
+                timing.part_1,
+                timing.part_1_stats.as_ref(),
+                timing.part_1_memory.as_ref()
+            ),
+            format_part(
+                timing.part_2,
+                timing.part_2_stats.as_ref(),
+                timing.part_2_memory.as_ref()
+            )
         ));
     }
 
     lines.push(String::new());
     lines.push(format!("**Total: {total_millis:.2}ms**"));
-    lines.push(MARKER.into());
 
-    lines.join("\n")
+    lines
 }
 
-fn update_content(s: &mut String, timings: Timings, total_millis: f64) -> Result<(), Error> {
+/// Renders one table per year, the most recent year first.
+fn update_content(s: &mut String, mut timings: Vec<(Year, Timings)>) -> Result<(), Error> {
     let positions = locate_table(s)?;
-    let table = construct_table("##", timings, total_millis);
-    s.replace_range(positions.pos_start..positions.pos_end, &table);
+
+    timings.sort_unstable_by_key(|(year, _)| std::cmp::Reverse(*year));
+
+    let mut lines: Vec<String> = vec![MARKER.into()];
+    for (i, (year, timings)) in timings.into_iter().enumerate() {
+        if i > 0 {
+            lines.push(String::new());
+        }
+        lines.extend(construct_table("##", year, timings));
+    }
+    lines.push(MARKER.into());
+
+    s.replace_range(positions.pos_start..positions.pos_end, &lines.join("\n"));
     Ok(())
 }
 
-pub fn update(timings: Timings) -> Result<(), Error> {
+/// Updates the readme with the stored timings of every year.
+pub fn update() -> Result<(), Error> {
     let path = "README.md";
     let mut readme = String::from_utf8_lossy(&fs::read(path)?).to_string();
-    let total_millis = timings.total_millis();
-    update_content(&mut readme, timings, total_millis)?;
+
+    let timings = Year::stored()
+        .into_iter()
+        .map(|year| (year, Timings::read_from_file(year)))
+        .filter(|(_, timings)| !timings.data.is_empty())
+        .collect();
+
+    update_content(&mut readme, timings)?;
     fs::write(path, &readme)?;
     Ok(())
 }
@@ -100,28 +162,31 @@ pub fn update(timings: Timings) -> Result<(), Error> {
 #[cfg(feature = "test_lib")]
 mod tests {
     use super::{update_content, MARKER};
-    use crate::{day, template::timings::Timing, template::timings::Timings};
+    use crate::{
+        day, template::memory::MemoryStats, template::stats::Stats, template::timings::Timing,
+        template::timings::Timings, year,
+    };
 
     fn get_mock_timings() -> Timings {
         Timings {
             data: vec![
                 Timing {
-                    day: day!(1),
                     part_1: Some("10ms".into()),
                     part_2: Some("20ms".into()),
-                    total_nanos: 3e+10,
+                    total_nanos: 3e+7,
+                    ..Timing::new(day!(1))
                 },
                 Timing {
-                    day: day!(2),
                     part_1: Some("30ms".into()),
                     part_2: Some("40ms".into()),
-                    total_nanos: 7e+10,
+                    total_nanos: 7e+7,
+                    ..Timing::new(day!(2))
                 },
                 Timing {
-                    day: day!(4),
                     part_1: Some("40ms".into()),
                     part_2: Some("50ms".into()),
-                    total_nanos: 9e+10,
+                    total_nanos: 9e+7,
+                    ..Timing::new(day!(4))
                 },
             ],
         }
@@ -131,47 +196,90 @@ mod tests {
     #[should_panic]
     fn errors_if_marker_not_present() {
         let mut s = "# readme".to_string();
-        update_content(&mut s, get_mock_timings(), 190.0).unwrap();
+        update_content(&mut s, vec![(year!(2024), get_mock_timings())]).unwrap();
     }
 
     #[test]
     #[should_panic]
     fn errors_if_too_many_markers_present() {
         let mut s = format!("{} {} {}", MARKER, MARKER, MARKER);
-        update_content(&mut s, get_mock_timings(), 190.0).unwrap();
+        update_content(&mut s, vec![(year!(2024), get_mock_timings())]).unwrap();
     }
 
     #[test]
     fn updates_empty_benchmarks() {
         let mut s = format!("foo\nbar\n{}{}\nbaz", MARKER, MARKER);
-        update_content(&mut s, get_mock_timings(), 190.0).unwrap();
+        update_content(&mut s, vec![(year!(2024), get_mock_timings())]).unwrap();
         assert_eq!(s.contains("## Benchmarks"), true);
     }
 
     #[test]
     fn updates_existing_benchmarks() {
         let mut s = format!("foo\nbar\n{}{}\nbaz", MARKER, MARKER);
-        update_content(&mut s, get_mock_timings(), 190.0).unwrap();
-        update_content(&mut s, get_mock_timings(), 190.0).unwrap();
+        update_content(&mut s, vec![(year!(2024), get_mock_timings())]).unwrap();
+        update_content(&mut s, vec![(year!(2024), get_mock_timings())]).unwrap();
         assert_eq!(s.matches(MARKER).collect::<Vec<&str>>().len(), 2);
         assert_eq!(s.matches("## Benchmarks").collect::<Vec<&str>>().len(), 1);
     }
 
+    #[test]
+    fn format_benchmarks_with_spread() {
+        let mut timings = get_mock_timings();
+        timings.data[0].part_1_stats = Some(Stats {
+            mean: 10_000_000.0,
+            min: 9_000_000.0,
+            max: 12_000_000.0,
+            median: 10_000_000.0,
+            p95: 11_000_000.0,
+            std_dev: 500_000.0,
+            samples: 100,
+            outliers: 0,
+            counters: None,
+        });
+        let mut s = format!("foo\nbar\n{}\n{}\nbaz", MARKER, MARKER);
+        update_content(&mut s, vec![(year!(2024), timings)]).unwrap();
+        assert!(s.contains("| [Day 1](./src/bin/2024-01.rs) | `10.0ms ± 500.0µs` | `20ms` |"));
+    }
+
+    #[test]
+    fn format_benchmarks_with_memory() {
+        let mut timings = get_mock_timings();
+        timings.data[0].part_2_memory = Some(MemoryStats {
+            peak_bytes: 3 * 1024 * 1024,
+            total_bytes: 5 * 1024 * 1024,
+            allocations: 100,
+        });
+        let mut s = format!("foo\nbar\n{}\n{}\nbaz", MARKER, MARKER);
+        update_content(&mut s, vec![(year!(2024), timings)]).unwrap();
+        assert!(s.contains("| [Day 1](./src/bin/2024-01.rs) | `10ms` | `20ms` · `3.0 MiB` |"));
+    }
+
+    #[test]
+    fn format_benchmarks_with_parse() {
+        let mut timings = get_mock_timings();
+        timings.data[1].parse = Some("5ms".into());
+        let mut s = format!("foo\nbar\n{}\n{}\nbaz", MARKER, MARKER);
+        update_content(&mut s, vec![(year!(2024), timings)]).unwrap();
+        assert!(s.contains("| Day | Parse | Part 1 | Part 2 |"));
+        assert!(s.contains("| [Day 1](./src/bin/2024-01.rs) | `-` | `10ms` | `20ms` |"));
+        assert!(s.contains("| [Day 2](./src/bin/2024-02.rs) | `5ms` | `30ms` | `40ms` |"));
+    }
+
     #[test]
     fn format_benchmarks() {
         let mut s = format!("foo\nbar\n{}\n{}\nbaz", MARKER, MARKER);
-        update_content(&mut s, get_mock_timings(), 190.0).unwrap();
+        update_content(&mut s, vec![(year!(2024), get_mock_timings())]).unwrap();
         let expected = [
             "foo",
             "bar",
             "<!--- benchmarking table --->",
-            "## Benchmarks",
+            "## Benchmarks 2024",
             "",
             "| Day | Part 1 | Part 2 |",
             "| :---: | :---: | :---:  |",
-            "| [Day 1](./src/bin/01.rs) | `10ms` | `20ms` |",
-            "| [Day 2](./src/bin/02.rs) | `30ms` | `40ms` |",
-            "| [Day 4](./src/bin/04.rs) | `40ms` | `50ms` |",
+            "| [Day 1](./src/bin/2024-01.rs) | `10ms` | `20ms` |",
+            "| [Day 2](./src/bin/2024-02.rs) | `30ms` | `40ms` |",
+            "| [Day 4](./src/bin/2024-04.rs) | `40ms` | `50ms` |",
             "",
             "**Total: 190.00ms**",
             "<!--- benchmarking table --->",
@@ -180,4 +288,22 @@ mod tests {
         .join("\n");
         assert_eq!(s, expected);
     }
+
+    #[test]
+    fn format_benchmarks_for_multiple_years() {
+        let mut s = format!("foo\n{}\n{}\nbaz", MARKER, MARKER);
+        update_content(
+            &mut s,
+            vec![
+                (year!(2023), get_mock_timings()),
+                (year!(2024), get_mock_timings()),
+            ],
+        )
+        .unwrap();
+        let pos_2023 = s.find("## Benchmarks 2023").unwrap();
+        let pos_2024 = s.find("## Benchmarks 2024").unwrap();
+        assert!(pos_2024 < pos_2023);
+        assert!(s.contains("| [Day 1](./src/bin/2023-01.rs) | `10ms` | `20ms` |"));
+        assert_eq!(s.matches(MARKER).count(), 2);
+    }
 }
@@ -0,0 +1,308 @@
+/// Machine-readable protocol between solution binaries and the processes that run them.
+/// When a binary is started with `--records <path>`, every part appends a JSON line to that file.
+use std::{
+    collections::HashMap,
+    fmt::Display,
+    fs::{self, OpenOptions},
+    io::{self, Write},
+    path::{Path, PathBuf},
+    str::FromStr,
+};
+
+use tinyjson::JsonValue;
+
+use crate::template::{memory::MemoryStats, runner, stats::Stats, Day};
+
+pub const RECORDS_ARG: &str = "--records";
+
+/// Part number used for records of the optional parse step.
+pub const PARSE_PART: u8 = 0;
+
+/// Outcome of running a single solution part.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Status {
+    Solved,
+    /// The part returned `None`, i.e. it is not solved yet.
+    Unsolved,
+    /// The part returned an error or panicked.
+    Error {
+        message: String,
+    },
+}
+
+impl Display for Status {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Status::Solved => f.write_str("solved"),
+            Status::Unsolved => f.write_str("unsolved"),
+            Status::Error { .. } => f.write_str("error"),
+        }
+    }
+}
+
+impl FromStr for Status {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "solved" => Ok(Status::Solved),
+            "unsolved" => Ok(Status::Unsolved),
+            s => Err(format!("unknown record status `{s}`.")),
+        }
+    }
+}
+
+/// Represents the result of running a single solution part.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Record {
+    pub day: Day,
+    pub part: u8,
+    pub answer: Option<String>,
+    pub nanos: f64,
+    pub samples: u128,
+    pub status: Status,
+    /// Benchmark statistics, only present for timed runs.
+    pub stats: Option<Stats>,
+    /// Heap usage of the first run, only present if the `memory` feature is enabled.
+    pub memory: Option<MemoryStats>,
+}
+
+impl Record {
+    /// Appends the record to the file passed via `--records`, if any.
+    pub fn emit(&self) -> Result<(), io::Error> {
+        let Some(path) = get_records_path() else {
+            return Ok(());
+        };
+
+        let line = JsonValue::from(self)
+            .stringify()
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e.to_string()))?;
+
+        let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+        writeln!(file, "{line}")
+    }
+}
+
+/// Reads all records from a file written by a solution binary. If not present, returns no records.
+pub fn read_records(path: &Path) -> Result<Vec<Record>, String> {
+    let Ok(content) = fs::read_to_string(path) else {
+        return Ok(vec![]);
+    };
+
+    content
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| {
+            let json = JsonValue::from_str(line).map_err(|_| "record is not valid JSON.")?;
+            Record::try_from(&json)
+        })
+        .collect()
+}
+
+fn get_records_path() -> Option<PathBuf> {
+    let args = runner::args();
+    let index = args.iter().position(|x| x == RECORDS_ARG)?;
+    args.get(index + 1).map(PathBuf::from)
+}
+
+/* -------------------------------------------------------------------------- */
+
+impl From<&Record> for JsonValue {
+    fn from(value: &Record) -> Self {
+        let mut map: HashMap<String, JsonValue> = HashMap::new();
+
+        map.insert("day".into(), JsonValue::String(value.day.to_string()));
+        map.insert("part".into(), JsonValue::Number(f64::from(value.part)));
+        map.insert(
+            "answer".into(),
+            match &value.answer {
+                Some(x) => JsonValue::String(x.clone()),
+                None => JsonValue::Null,
+            },
+        );
+        map.insert("nanos".into(), JsonValue::Number(value.nanos));
+        #[allow(clippy::cast_precision_loss)]
+        map.insert("samples".into(), JsonValue::Number(value.samples as f64));
+        map.insert("status".into(), JsonValue::String(value.status.to_string()));
+        map.insert(
+            "message".into(),
+            match &value.status {
+                Status::Error { message } => JsonValue::String(message.clone()),
+                _ => JsonValue::Null,
+            },
+        );
+        map.insert(
+            "stats".into(),
+            match &value.stats {
+                Some(x) => JsonValue::from(x),
+                None => JsonValue::Null,
+            },
+        );
+        map.insert(
+            "memory".into(),
+            match &value.memory {
+                Some(x) => JsonValue::from(x),
+                None => JsonValue::Null,
+            },
+        );
+
+        JsonValue::Object(map)
+    }
+}
+
+impl TryFrom<&JsonValue> for Record {
+    type Error = String;
+
+    fn try_from(value: &JsonValue) -> Result<Self, Self::Error> {
+        let json = value
+            .get::<HashMap<String, JsonValue>>()
+            .ok_or("Expected record to be a JSON object.")?;
+
+        let day = json
+            .get("day")
+            .and_then(|v| v.get::<String>())
+            .and_then(|day| Day::from_str(day).ok())
+            .ok_or("Expected record.day to be a Day struct.")?;
+
+        #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+        let part = json
+            .get("part")
+            .and_then(|v| v.get::<f64>())
+            .map(|x| *x as u8)
+            .ok_or("Expected record.part to be a number.")?;
+
+        let answer = json
+            .get("answer")
+            .map(|v| if v.is_null() { None } else { v.get::<String>() })
+            .ok_or("Expected record.answer to be null or string.")?;
+
+        let nanos = json
+            .get("nanos")
+            .and_then(|v| v.get::<f64>().copied())
+            .ok_or("Expected record.nanos to be a number.")?;
+
+        #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+        let samples = json
+            .get("samples")
+            .and_then(|v| v.get::<f64>())
+            .map(|x| *x as u128)
+            .ok_or("Expected record.samples to be a number.")?;
+
+        let status = match json
+            .get("status")
+            .and_then(|v| v.get::<String>())
+            .ok_or("Expected record.status to be a string.")?
+            .as_str()
+        {
+            "error" => Status::Error {
+                message: json
+                    .get("message")
+                    .and_then(|v| v.get::<String>())
+                    .cloned()
+                    .ok_or("Expected record.message to be a string.")?,
+            },
+            status => status.parse()?,
+        };
+
+        let stats = match json.get("stats") {
+            Some(v) if !v.is_null() => Some(Stats::try_from(v)?),
+            _ => None,
+        };
+
+        let memory = match json.get("memory") {
+            Some(v) if !v.is_null() => Some(MemoryStats::try_from(v)?),
+            _ => None,
+        };
+
+        Ok(Record {
+            day,
+            part,
+            answer: answer.cloned(),
+            nanos,
+            samples,
+            status,
+            stats,
+            memory,
+        })
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::{Record, Status};
+    use crate::{
+        day,
+        template::{memory::MemoryStats, stats::Stats},
+    };
+    use std::str::FromStr;
+    use tinyjson::JsonValue;
+
+    #[test]
+    fn roundtrips_records() {
+        let record = Record {
+            day: day!(3),
+            part: 2,
+            answer: Some("@ ( ) samples)\n#..#".into()),
+            nanos: 74.13,
+            samples: 100_000,
+            status: Status::Solved,
+            stats: Some(Stats {
+                mean: 74.13,
+                min: 70.0,
+                max: 120.0,
+                median: 73.0,
+                p95: 80.0,
+                std_dev: 3.2,
+                samples: 100_000,
+                outliers: 12,
+                counters: None,
+            }),
+            memory: Some(MemoryStats {
+                peak_bytes: 2048,
+                total_bytes: 8192,
+                allocations: 3,
+            }),
+        };
+        let line = JsonValue::from(&record).stringify().unwrap();
+        let parsed = Record::try_from(&JsonValue::from_str(&line).unwrap()).unwrap();
+        assert_eq!(parsed, record);
+    }
+
+    #[test]
+    fn handles_unsolved_records() {
+        let json = r#"{ "day": "01", "part": 1, "answer": null, "nanos": 10, "samples": 1, "status": "unsolved" }"#;
+        let record = Record::try_from(&JsonValue::from_str(json).unwrap()).unwrap();
+        assert_eq!(record.answer, None);
+        assert_eq!(record.status, Status::Unsolved);
+        assert_eq!(record.stats, None);
+        assert_eq!(record.memory, None);
+    }
+
+    #[test]
+    fn roundtrips_error_records() {
+        let record = Record {
+            day: day!(5),
+            part: 2,
+            answer: None,
+            nanos: 10.0,
+            samples: 1,
+            status: Status::Error {
+                message: "panicked: index out of bounds".into(),
+            },
+            stats: None,
+            memory: None,
+        };
+        let line = JsonValue::from(&record).stringify().unwrap();
+        let parsed = Record::try_from(&JsonValue::from_str(&line).unwrap()).unwrap();
+        assert_eq!(parsed, record);
+    }
+
+    #[test]
+    #[should_panic]
+    fn panics_for_malformed_records() {
+        let json = r#"{ "day": "01", "part": 1 }"#;
+        Record::try_from(&JsonValue::from_str(json).unwrap()).unwrap();
+    }
+}
@@ -0,0 +1,27 @@
+/// Solutions that are compiled into the main binary when the `registry` feature is enabled.
+/// Registered solutions are run in-process by `cargo all` and `cargo time` instead of spawning a binary per day.
+use std::sync::OnceLock;
+
+use crate::template::Puzzle;
+
+/// A solution that can be run in-process.
+pub struct Solution {
+    pub puzzle: Puzzle,
+    /// The `main` function generated by the `solution!` macro.
+    pub main: fn(),
+}
+
+static SOLUTIONS: OnceLock<&'static [Solution]> = OnceLock::new();
+
+/// Registers the solutions compiled into the main binary. Only the first call has an effect.
+pub fn register(solutions: &'static [Solution]) {
+    let _ = SOLUTIONS.set(solutions);
+}
+
+/// Returns the registered solution of `puzzle`, if any.
+pub fn get(puzzle: Puzzle) -> Option<&'static Solution> {
+    SOLUTIONS
+        .get()?
+        .iter()
+        .find(|solution| solution.puzzle == puzzle)
+}
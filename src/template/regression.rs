@@ -0,0 +1,165 @@
+/// Module that compares fresh benchmark results against the stored baseline.
+use crate::template::{
+    record::PARSE_PART, stats::format_nanos, timings::Timings, Day, ANSI_BOLD, ANSI_GREEN,
+    ANSI_RED, ANSI_RESET,
+};
+
+/// Default relative change (in percent) above which a part is flagged.
+pub const DEFAULT_THRESHOLD: f64 = 10.0;
+
+/// A part whose timing changed by more than the threshold.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Delta {
+    pub day: Day,
+    /// Part number, `0` refers to the parse step.
+    pub part: u8,
+    pub baseline_nanos: f64,
+    pub current_nanos: f64,
+}
+
+impl Delta {
+    /// Relative change in percent, positive if the part got slower.
+    pub fn change(&self) -> f64 {
+        (self.current_nanos - self.baseline_nanos) / self.baseline_nanos * 100.0
+    }
+
+    pub fn is_regression(&self) -> bool {
+        self.current_nanos > self.baseline_nanos
+    }
+}
+
+/// Returns all parts present in both `baseline` and `current` that changed by more than `threshold` percent.
+pub fn compare(baseline: &Timings, current: &Timings, threshold: f64) -> Vec<Delta> {
+    let mut deltas = vec![];
+
+    for timing in &current.data {
+        let Some(stored) = baseline.data.iter().find(|t| t.day == timing.day) else {
+            continue;
+        };
+
+        for part in [PARSE_PART, 1, 2] {
+            let (Some(baseline_nanos), Some(current_nanos)) =
+                (stored.part_nanos(part), timing.part_nanos(part))
+            else {
+                continue;
+            };
+
+            if baseline_nanos <= 0.0 {
+                continue;
+            }
+
+            let delta = Delta {
+                day: timing.day,
+                part,
+                baseline_nanos,
+                current_nanos,
+            };
+
+            if delta.change().abs() > threshold {
+                deltas.push(delta);
+            }
+        }
+    }
+
+    deltas
+}
+
+/// Prints flagged parts with colored deltas.
+pub fn print_deltas(deltas: &[Delta], threshold: f64) {
+    println!();
+
+    if deltas.is_empty() {
+        println!("No changes above {threshold}% compared to stored timings.");
+        return;
+    }
+
+    println!("{ANSI_BOLD}Changes above {threshold}% compared to stored timings:{ANSI_RESET}");
+
+    for delta in deltas {
+        let label = if delta.part == PARSE_PART {
+            "Parse".to_string()
+        } else {
+            format!("Part {}", delta.part)
+        };
+
+        let color = if delta.is_regression() {
+            ANSI_RED
+        } else {
+            ANSI_GREEN
+        };
+
+        println!(
+            "Day {} {label}: {} → {} {color}({:+.1}%){ANSI_RESET}",
+            delta.day,
+            format_nanos(delta.baseline_nanos),
+            format_nanos(delta.current_nanos),
+            delta.change()
+        );
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::compare;
+    use crate::{
+        day,
+        template::{
+            timings::{Timing, Timings},
+            Day,
+        },
+    };
+
+    fn timing(day: u8, part_1: Option<&str>, part_2: Option<&str>) -> Timing {
+        Timing {
+            part_1: part_1.map(Into::into),
+            part_2: part_2.map(Into::into),
+            ..Timing::new(Day::new(day).unwrap())
+        }
+    }
+
+    #[test]
+    fn flags_regressions_and_improvements() {
+        let baseline = Timings {
+            data: vec![timing(1, Some("10.0ms"), Some("20.0ms"))],
+        };
+        let current = Timings {
+            data: vec![timing(1, Some("12.0ms"), Some("10.0ms"))],
+        };
+        let deltas = compare(&baseline, &current, 10.0);
+
+        assert_eq!(deltas.len(), 2);
+        assert_eq!(deltas[0].day, day!(1));
+        assert_eq!(deltas[0].part, 1);
+        assert!(deltas[0].is_regression());
+        assert!((deltas[0].change() - 20.0).abs() < 1e-6);
+        assert!(!deltas[1].is_regression());
+        assert!((deltas[1].change() + 50.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn ignores_changes_below_threshold() {
+        let baseline = Timings {
+            data: vec![timing(1, Some("10.0ms"), Some("20.0ms"))],
+        };
+        let current = Timings {
+            data: vec![timing(1, Some("10.5ms"), Some("19.0ms"))],
+        };
+        assert!(compare(&baseline, &current, 10.0).is_empty());
+    }
+
+    #[test]
+    fn ignores_parts_without_baseline() {
+        let baseline = Timings {
+            data: vec![timing(1, Some("10.0ms"), None)],
+        };
+        let current = Timings {
+            data: vec![
+                timing(1, Some("10.0ms"), Some("50.0ms")),
+                timing(2, Some("1.0s"), None),
+            ],
+        };
+        assert!(compare(&baseline, &current, 10.0).is_empty());
+    }
+}
@@ -1,40 +1,99 @@
-use std::{collections::HashSet, io};
+use std::{collections::HashSet, fmt::Display, io, process, time::Duration};
 
-use crate::template::{Day, ANSI_BOLD, ANSI_ITALIC, ANSI_RESET};
+use crate::template::{Day, Puzzle, Year, ANSI_BOLD, ANSI_ITALIC, ANSI_RED, ANSI_RESET};
 
 use super::{
     all_days,
+    record::{Record, Status, PARSE_PART},
+    registry,
     timings::{Timing, Timings},
 };
 
-pub fn run_multi(days_to_run: &HashSet<Day>, is_release: bool, is_timed: bool) -> Option<Timings> {
-    let mut timings: Vec<Timing> = Vec::with_capacity(days_to_run.len());
+/// Output of running a set of days.
+pub struct MultiRun {
+    /// Timings of all solved days, only present for timed runs.
+    pub timings: Option<Timings>,
+    /// Records emitted by all parts of all days.
+    pub records: Vec<Record>,
+}
 
-    let mut need_space = false;
+/// Runs the solutions of `days_to_run` and prints their output grouped per day.
+///
+/// Days that are registered in the [`registry`] are run in-process, all other days spawn their binary.
+/// With `jobs > 1`, all binaries are built up front and then run on `jobs` threads.
+/// The output of each day is buffered and printed in order once the day has finished.
+pub fn run_multi(
+    year: Year,
+    days_to_run: &HashSet<Day>,
+    is_release: bool,
+    is_timed: bool,
+    bench_budget: Option<Duration>,
+    jobs: usize,
+) -> MultiRun {
+    let mut timings: Vec<Timing> = Vec::with_capacity(days_to_run.len());
+    let mut all_records: Vec<Record> = vec![];
 
     // NOTE: use non-duplicate, sorted day values.
-    all_days()
+    let puzzles: Vec<Puzzle> = all_days(year)
         .filter(|day| days_to_run.contains(day))
-        .for_each(|day| {
-            if need_space {
-                println!();
-            }
-            need_space = true;
-
-            println!("{ANSI_BOLD}Day {day}{ANSI_RESET}");
-            println!("------");
+        .map(|day| Puzzle::new(year, day))
+        .collect();
+
+    let mut collect = |day: Day, records: Vec<Record>| {
+        if records.is_empty() {
+            println!("Not solved.");
+        } else {
+            let val = child_commands::timing_from_records(&records, day);
+            timings.push(val);
+            all_records.extend(records);
+        }
+    };
 
-            let output = child_commands::run_solution(day, is_timed, is_release).unwrap();
+    if jobs > 1 {
+        // cargo already printed the compiler errors of a failed build.
+        if let Err(e) = child_commands::build_solutions(is_release) {
+            eprintln!("Failed to build the solutions: {e}");
+            process::exit(1);
+        }
 
-            if output.is_empty() {
-                println!("Not solved.");
-            } else {
-                let val = child_commands::parse_exec_time(&output, day);
-                timings.push(val);
-            }
-        });
+        child_commands::run_solutions_parallel(
+            &puzzles,
+            is_timed,
+            is_release,
+            bench_budget,
+            jobs,
+            |index, puzzle, run| {
+                print_header(puzzle.day, index > 0);
+                match run {
+                    Ok(run) => {
+                        print!("{}", run.stdout);
+                        eprint!("{}", run.stderr);
+                        collect(puzzle.day, run.records);
+                    }
+                    Err(e) => {
+                        eprintln!("Failed to run {puzzle}: {e}");
+                        collect(puzzle.day, vec![]);
+                    }
+                }
+            },
+        );
+    } else {
+        for (index, puzzle) in puzzles.iter().enumerate() {
+            print_header(puzzle.day, index > 0);
+            let records = match registry::get(*puzzle) {
+                Some(solution) => child_commands::run_in_process(solution, is_timed, bench_budget),
+                None => child_commands::run_solution(*puzzle, is_timed, is_release, bench_budget),
+            };
+            // a day that can not be run counts as not solved, the other days still run.
+            let records = records.unwrap_or_else(|e| {
+                eprintln!("Failed to run {puzzle}: {e}");
+                vec![]
+            });
+            collect(puzzle.day, records);
+        }
+    }
 
-    if is_timed {
+    let timings = if is_timed {
         let timings = Timings { data: timings };
         let total_millis = timings.total_millis();
         println!(
@@ -43,14 +102,73 @@ pub fn run_multi(days_to_run: &HashSet<Day>, is_release: bool, is_timed: bool) -
         Some(timings)
     } else {
         None
+    };
+
+    print_errors(&all_records);
+
+    MultiRun {
+        timings,
+        records: all_records,
+    }
+}
+
+fn print_header(day: Day, need_space: bool) {
+    if need_space {
+        println!();
+    }
+    println!("{ANSI_BOLD}Day {day}{ANSI_RESET}");
+    println!("------");
+}
+
+/// Lists the parts that returned an error or panicked, so that they are not lost in the output of all days.
+fn print_errors(records: &[Record]) {
+    let errors: Vec<(&Record, &str)> = records
+        .iter()
+        .filter_map(|r| match &r.status {
+            Status::Error { message } => Some((r, message.as_str())),
+            _ => None,
+        })
+        .collect();
+
+    if errors.is_empty() {
+        return;
+    }
+
+    println!();
+    println!("{ANSI_BOLD}Errors{ANSI_RESET}");
+    println!("------");
+
+    for (record, message) in errors {
+        let label = match record.part {
+            PARSE_PART => "Parse".to_string(),
+            part => format!("Part {part}"),
+        };
+        // multi-line errors, e.g. parse errors with context, are shown by the day's own output.
+        let message = message.lines().next().unwrap_or_default();
+        println!(
+            "Day {} {label}: {ANSI_RED}{message}{ANSI_RESET}",
+            record.day
+        );
     }
 }
 
-#[allow(dead_code)]
 #[derive(Debug)]
 pub enum Error {
     BrokenPipe,
+    BuildFailed,
     IO(io::Error),
+    Records(String),
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Error::BrokenPipe => f.write_str("could not read the output of the solution."),
+            Error::BuildFailed => f.write_str("cargo build failed."),
+            Error::IO(e) => write!(f, "{e}"),
+            Error::Records(e) => write!(f, "could not read records: {e}"),
+        }
+    }
 }
 
 impl From<std::io::Error> for Error {
@@ -59,45 +177,72 @@ impl From<std::io::Error> for Error {
     }
 }
 
-#[must_use]
-pub fn get_path_for_bin(day: Day) -> String {
-    format!("./src/bin/{day}.rs")
-}
-
 /// All solutions live in isolated binaries.
-/// This module encapsulates interaction with these binaries, both invoking them as well as parsing the timing output.
+/// This module encapsulates interaction with these binaries, both invoking them as well as collecting the records they emit.
 pub mod child_commands {
-    use super::{get_path_for_bin, Error};
-    use crate::template::Day;
+    use super::Error;
+    use crate::template::{
+        record::{read_records, Record, Status, PARSE_PART, RECORDS_ARG},
+        registry::Solution,
+        runner::{with_args, BUDGET_ARG},
+        stats::format_nanos,
+        Day, Puzzle,
+    };
     use std::{
+        collections::BTreeMap,
+        env, fs,
         io::{BufRead, BufReader},
-        path::Path,
-        process::{Command, Stdio},
+        panic::{self, AssertUnwindSafe},
+        path::{Path, PathBuf},
+        process::{self, Command, Stdio},
+        sync::{
+            atomic::{AtomicUsize, Ordering},
+            mpsc,
+        },
         thread,
+        time::Duration,
     };
 
-    /// Run the solution bin for a given day
-    pub fn run_solution(day: Day, is_timed: bool, is_release: bool) -> Result<Vec<String>, Error> {
+    /// Output of a solution binary that was run with buffered output.
+    pub struct BufferedRun {
+        pub stdout: String,
+        pub stderr: String,
+        pub records: Vec<Record>,
+    }
+
+    /// Run the solution bin for a given puzzle and return the records it emitted.
+    pub fn run_solution(
+        puzzle: Puzzle,
+        is_timed: bool,
+        is_release: bool,
+        bench_budget: Option<Duration>,
+    ) -> Result<Vec<Record>, Error> {
         // skip command invocation for days that have not been scaffolded yet.
-        if !Path::new(&get_path_for_bin(day)).exists() {
+        if !puzzle.bin_path().exists() {
             return Ok(vec![]);
         }
 
-        let day_padded = day.to_string();
-        let mut args = vec!["run", "--quiet", "--bin", &day_padded];
+        let records_path = get_records_path(puzzle);
+        let _ = fs::remove_file(&records_path);
+
+        let mut args = vec![
+            "run".to_string(),
+            "--quiet".to_string(),
+            "--bin".to_string(),
+            puzzle.to_string(),
+        ];
 
         if is_release {
-            args.push("--release");
+            args.push("--release".to_string());
         }
 
-        if is_timed {
-            // mirror `--time` flag to child invocations.
-            args.push("--");
-            args.push("--time");
-        }
+        args.extend(get_feature_args());
+
+        args.push("--".to_string());
+        args.extend(get_solution_args(&records_path, is_timed, bench_budget));
 
         // spawn child command with piped stdout/stderr.
-        // forward output to stdout/stderr while grabbing stdout lines.
+        // forward output to stdout/stderr, the records are read from their own file afterwards.
 
         let mut cmd = Command::new("cargo")
             .args(&args)
@@ -108,85 +253,271 @@ pub mod child_commands {
         let stdout = BufReader::new(cmd.stdout.take().ok_or(super::Error::BrokenPipe)?);
         let stderr = BufReader::new(cmd.stderr.take().ok_or(super::Error::BrokenPipe)?);
 
-        let mut output = vec![];
-
         let thread = thread::spawn(move || {
-            stderr.lines().for_each(|line| {
-                eprintln!("{}", line.unwrap());
+            stderr.lines().map_while(Result::ok).for_each(|line| {
+                eprintln!("{line}");
             });
         });
 
-        for line in stdout.lines() {
-            let line = line.unwrap();
+        for line in stdout.lines().map_while(Result::ok) {
             println!("{line}");
-            output.push(line);
         }
 
-        thread.join().unwrap();
+        thread.join().map_err(|_| Error::BrokenPipe)?;
         cmd.wait()?;
 
-        Ok(output)
+        let records = read_records(&records_path).map_err(Error::Records)?;
+        let _ = fs::remove_file(&records_path);
+
+        Ok(records)
     }
 
-    pub fn parse_exec_time(output: &[String], day: Day) -> super::Timing {
-        let mut timings = super::Timing {
-            day,
-            part_1: None,
-            part_2: None,
-            total_nanos: 0_f64,
-        };
+    /// Run the solution bin for a given puzzle through cargo and capture its output.
+    pub fn run_solution_captured(puzzle: Puzzle, is_release: bool) -> Result<BufferedRun, Error> {
+        let records_path = get_records_path(puzzle);
+        let _ = fs::remove_file(&records_path);
 
-        output
-            .iter()
-            .filter_map(|l| {
-                if !l.contains(" samples)") {
-                    return None;
-                }
+        let mut args = vec![
+            "run".to_string(),
+            "--quiet".to_string(),
+            "--bin".to_string(),
+            puzzle.to_string(),
+        ];
+
+        if is_release {
+            args.push("--release".to_string());
+        }
+
+        args.extend(get_feature_args());
+
+        args.push("--".to_string());
+        args.extend(get_solution_args(&records_path, false, None));
+
+        let output = Command::new("cargo").args(&args).output()?;
+
+        let records = read_records(&records_path).map_err(Error::Records)?;
+        let _ = fs::remove_file(&records_path);
+
+        Ok(BufferedRun {
+            stdout: String::from_utf8_lossy(&output.stdout).into_owned(),
+            stderr: String::from_utf8_lossy(&output.stderr).into_owned(),
+            records,
+        })
+    }
+
+    /// Run a solution that is compiled into the main binary and return the records it emitted.
+    /// A panicking solution is reported like a failing binary, i.e. its remaining parts are not solved.
+    pub fn run_in_process(
+        solution: &Solution,
+        is_timed: bool,
+        bench_budget: Option<Duration>,
+    ) -> Result<Vec<Record>, Error> {
+        let records_path = get_records_path(solution.puzzle);
+        let _ = fs::remove_file(&records_path);
+
+        let mut args = vec![solution.puzzle.to_string()];
+        args.extend(get_solution_args(&records_path, is_timed, bench_budget));
+
+        let _ = with_args(args, || {
+            panic::catch_unwind(AssertUnwindSafe(solution.main))
+        });
+
+        let records = read_records(&records_path).map_err(Error::Records)?;
+        let _ = fs::remove_file(&records_path);
+
+        Ok(records)
+    }
+
+    /// Build all solution binaries, so that they can be invoked without going through cargo.
+    pub fn build_solutions(is_release: bool) -> Result<(), Error> {
+        let mut args = vec!["build", "--quiet", "--bins"];
+
+        if is_release {
+            args.push("--release");
+        }
+
+        let feature_args = get_feature_args();
+        args.extend(feature_args.iter().map(String::as_str));
+
+        let status = Command::new("cargo")
+            .args(&args)
+            .stdout(Stdio::inherit())
+            .stderr(Stdio::inherit())
+            .status()?;
+
+        if status.success() {
+            Ok(())
+        } else {
+            Err(Error::BuildFailed)
+        }
+    }
+
+    /// Run the prebuilt solution bins of `puzzles` on `jobs` threads with buffered output.
+    /// `on_done` is called once per puzzle, in the order of `puzzles`, as soon as all previous puzzles have finished.
+    /// A puzzle that can not be run is passed to `on_done` as an error, the other puzzles still run.
+    pub fn run_solutions_parallel(
+        puzzles: &[Puzzle],
+        is_timed: bool,
+        is_release: bool,
+        bench_budget: Option<Duration>,
+        jobs: usize,
+        mut on_done: impl FnMut(usize, Puzzle, Result<BufferedRun, Error>),
+    ) {
+        let next = AtomicUsize::new(0);
+        let (sender, receiver) = mpsc::channel();
+
+        thread::scope(|scope| {
+            for _ in 0..jobs.min(puzzles.len()) {
+                let sender = sender.clone();
+                let next = &next;
+
+                scope.spawn(move || loop {
+                    let index = next.fetch_add(1, Ordering::Relaxed);
+                    let Some(puzzle) = puzzles.get(index) else {
+                        break;
+                    };
+                    let run = run_solution_buffered(*puzzle, is_timed, is_release, bench_budget);
+                    if sender.send((index, run)).is_err() {
+                        break;
+                    }
+                });
+            }
 
-                let Some((timing_str, nanos)) = parse_time(l) else {
-                    eprintln!("Could not parse timings from line: {l}");
-                    return None;
-                };
-
-                let part = l.split(':').next()?;
-                Some((part, timing_str, nanos))
-            })
-            .for_each(|(part, timing_str, nanos)| {
-                if part.contains("Part 1") {
-                    timings.part_1 = Some(timing_str.into());
-                } else if part.contains("Part 2") {
-                    timings.part_2 = Some(timing_str.into());
+            // drop the original sender so that the receiver finishes once all workers are done.
+            drop(sender);
+
+            let mut pending: BTreeMap<usize, Result<BufferedRun, Error>> = BTreeMap::new();
+            let mut next_to_print = 0;
+
+            for (index, run) in receiver {
+                pending.insert(index, run);
+
+                while let Some(run) = pending.remove(&next_to_print) {
+                    on_done(next_to_print, puzzles[next_to_print], run);
+                    next_to_print += 1;
                 }
+            }
+        });
+    }
 
-                timings.total_nanos += nanos;
+    fn run_solution_buffered(
+        puzzle: Puzzle,
+        is_timed: bool,
+        is_release: bool,
+        bench_budget: Option<Duration>,
+    ) -> Result<BufferedRun, Error> {
+        // skip command invocation for days that have not been scaffolded yet.
+        if !puzzle.bin_path().exists() {
+            return Ok(BufferedRun {
+                stdout: String::new(),
+                stderr: String::new(),
+                records: vec![],
             });
+        }
 
-        timings
+        let records_path = get_records_path(puzzle);
+        let _ = fs::remove_file(&records_path);
+
+        let output = Command::new(get_bin_path(puzzle, is_release))
+            .args(get_solution_args(&records_path, is_timed, bench_budget))
+            .output()?;
+
+        let records = read_records(&records_path).map_err(Error::Records)?;
+        let _ = fs::remove_file(&records_path);
+
+        Ok(BufferedRun {
+            stdout: String::from_utf8_lossy(&output.stdout).into_owned(),
+            stderr: String::from_utf8_lossy(&output.stderr).into_owned(),
+            records,
+        })
     }
 
-    fn parse_to_float(s: &str, postfix: &str) -> Option<f64> {
-        s.split(postfix).next()?.parse().ok()
+    /// Arguments passed to a solution binary.
+    fn get_solution_args(
+        records_path: &Path,
+        is_timed: bool,
+        bench_budget: Option<Duration>,
+    ) -> Vec<String> {
+        let mut args = vec![
+            RECORDS_ARG.to_string(),
+            records_path.to_string_lossy().into_owned(),
+        ];
+
+        if is_timed {
+            // mirror `--time` and `--budget` flags to child invocations.
+            args.push("--time".to_string());
+
+            if let Some(budget) = bench_budget {
+                args.extend([BUDGET_ARG.to_string(), budget.as_millis().to_string()]);
+            }
+        }
+
+        args
+    }
+
+    /// Features of the main binary that change how solutions are measured, passed on to solution binaries.
+    fn get_feature_args() -> Vec<String> {
+        let features: Vec<&str> = [
+            ("memory", cfg!(feature = "memory")),
+            ("perf", cfg!(feature = "perf")),
+        ]
+        .into_iter()
+        .filter_map(|(feature, is_enabled)| is_enabled.then_some(feature))
+        .collect();
+
+        if features.is_empty() {
+            vec![]
+        } else {
+            vec!["--features".to_string(), features.join(",")]
+        }
     }
 
-    fn parse_time(line: &str) -> Option<(&str, f64)> {
-        // for possible time formats, see: https://github.com/rust-lang/rust/blob/1.64.0/library/core/src/time.rs#L1176-L1200
-        let str_timing = line
-            .split(" samples)")
-            .next()?
-            .split('(')
-            .last()?
-            .split('@')
-            .next()?
-            .trim();
-
-        let parsed_timing = match str_timing {
-            s if s.contains("ns") => s.split("ns").next()?.parse::<f64>().ok(),
-            s if s.contains("µs") => parse_to_float(s, "µs").map(|x| x * 1000_f64),
-            s if s.contains("ms") => parse_to_float(s, "ms").map(|x| x * 1_000_000_f64),
-            s => parse_to_float(s, "s").map(|x| x * 1_000_000_000_f64),
-        }?;
-
-        Some((str_timing, parsed_timing))
+    /// Path of a binary built by [`build_solutions`].
+    fn get_bin_path(puzzle: Puzzle, is_release: bool) -> PathBuf {
+        let target_dir = env::var("CARGO_TARGET_DIR").unwrap_or_else(|_| "target".into());
+        let profile = if is_release { "release" } else { "debug" };
+
+        PathBuf::from(target_dir)
+            .join(profile)
+            .join(format!("{puzzle}{}", env::consts::EXE_SUFFIX))
+    }
+
+    fn get_records_path(puzzle: Puzzle) -> PathBuf {
+        env::temp_dir().join(format!("aoc-records-{}-{puzzle}.jsonl", process::id()))
+    }
+
+    pub fn timing_from_records(records: &[Record], day: Day) -> super::Timing {
+        let mut timings = super::Timing::new(day);
+
+        records
+            .iter()
+            .filter(|r| r.day == day && r.status == Status::Solved)
+            .for_each(|r| {
+                let timing_str = format_nanos(r.nanos);
+
+                match r.part {
+                    PARSE_PART => {
+                        timings.parse = Some(timing_str);
+                        timings.parse_stats.clone_from(&r.stats);
+                        timings.parse_memory = r.memory;
+                    }
+                    1 => {
+                        timings.part_1 = Some(timing_str);
+                        timings.part_1_stats.clone_from(&r.stats);
+                        timings.part_1_memory = r.memory;
+                    }
+                    2 => {
+                        timings.part_2 = Some(timing_str);
+                        timings.part_2_stats.clone_from(&r.stats);
+                        timings.part_2_memory = r.memory;
+                    }
+                    _ => return,
+                }
+
+                timings.total_nanos += r.nanos;
+            });
+
+        timings
     }
 
     /// copied from: https://github.com/rust-lang/rust/blob/1.64.0/library/std/src/macros.rs#L328-L333
@@ -205,50 +536,91 @@ pub mod child_commands {
 
     #[cfg(feature = "test_lib")]
     mod tests {
-        use super::parse_exec_time;
+        use super::timing_from_records;
 
-        use crate::day;
+        use crate::{
+            day,
+            template::{
+                record::{Record, Status},
+                stats::Stats,
+            },
+        };
+        use std::time::Duration;
+
+        fn record(part: u8, answer: Option<&str>, nanos: f64, samples: u128) -> Record {
+            Record {
+                day: day!(1),
+                part,
+                answer: answer.map(Into::into),
+                nanos,
+                samples,
+                status: if answer.is_some() {
+                    Status::Solved
+                } else {
+                    Status::Unsolved
+                },
+                stats: None,
+                memory: None,
+            }
+        }
 
         #[test]
-        fn parses_execution_times() {
-            let res = parse_exec_time(
+        fn collects_execution_times() {
+            let res = timing_from_records(
                 &[
-                    "Part 1: 0 (74.13ns @ 100000 samples)".into(),
-                    "Part 2: 10 (74.13ms @ 99999 samples)".into(),
-                    "".into(),
+                    record(1, Some("0"), 74.13, 100_000),
+                    record(2, Some("10"), 74_130_000.0, 99_999),
                 ],
                 day!(1),
             );
             assert_approx_eq!(res.total_nanos, 74130074.13_f64);
-            assert_eq!(res.part_1.unwrap(), "74.13ns");
-            assert_eq!(res.part_2.unwrap(), "74.13ms");
+            assert_eq!(res.part_1.unwrap(), "74.0ns");
+            assert_eq!(res.part_2.unwrap(), "74.1ms");
         }
 
         #[test]
-        fn parses_with_patterns_in_input() {
-            let res = parse_exec_time(
+        fn collects_with_patterns_in_answer() {
+            let res = timing_from_records(
                 &[
-                    "Part 1: @ @ @ ( ) ms (2s @ 5 samples)".into(),
-                    "Part 2: 10s (100ms @ 1 samples)".into(),
-                    "".into(),
+                    record(1, Some("@ @ @ ( ) ms"), 2_000_000_000.0, 5),
+                    record(2, Some("10s"), 100_000_000.0, 1),
                 ],
                 day!(1),
             );
             assert_approx_eq!(res.total_nanos, 2100000000_f64);
-            assert_eq!(res.part_1.unwrap(), "2s");
-            assert_eq!(res.part_2.unwrap(), "100ms");
+            assert_eq!(res.part_1.unwrap(), "2.0s");
+            assert_eq!(res.part_2.unwrap(), "100.0ms");
         }
 
         #[test]
-        fn parses_missing_parts() {
-            let res = parse_exec_time(
+        fn collects_stats() {
+            let mut part_1 = record(1, Some("1"), 2.0, 3);
+            part_1.stats = Stats::from_samples(&[Duration::from_nanos(1), Duration::from_nanos(3)]);
+            let res = timing_from_records(&[part_1, record(2, Some("2"), 5.0, 1)], day!(1));
+            assert_eq!(res.part_1_stats.unwrap().samples, 2);
+            assert_eq!(res.part_2_stats, None);
+        }
+
+        #[test]
+        fn collects_parse_times() {
+            let res = timing_from_records(
                 &[
-                    "Part 1: ✖        ".into(),
-                    "Part 2: ✖        ".into(),
-                    "".into(),
+                    record(0, Some(""), 1_000.0, 10),
+                    record(1, Some("1"), 2_000.0, 10),
+                    record(2, Some("2"), 3_000.0, 10),
                 ],
                 day!(1),
             );
+            assert_approx_eq!(res.total_nanos, 6_000_f64);
+            assert_eq!(res.parse.unwrap(), "1.0µs");
+        }
+
+        #[test]
+        fn collects_missing_parts() {
+            let res = timing_from_records(
+                &[record(1, None, 10.0, 1), record(2, None, 10.0, 1)],
+                day!(1),
+            );
             assert_approx_eq!(res.total_nanos, 0_f64);
             assert_eq!(res.part_1.is_none(), true);
             assert_eq!(res.part_2.is_none(), true);
@@ -1,101 +1,313 @@
 /// Encapsulates code that interacts with solution functions.
+use std::cell::RefCell;
 use std::fmt::Display;
 use std::hint::black_box;
 use std::io::{stdout, Write};
-use std::process::Output;
+use std::panic::{self, AssertUnwindSafe};
+use std::thread;
 use std::time::{Duration, Instant};
-use std::{cmp, env, process};
+use std::{env, process};
 
-use crate::template::ANSI_BOLD;
-use crate::template::{aoc_cli, Day, ANSI_ITALIC, ANSI_RESET};
+use crate::template::answers::KnownAnswers;
+use crate::template::history::current_timestamp;
+use crate::template::memory::{self, MemoryStats};
+use crate::template::perf;
+use crate::template::record::{Record, Status, PARSE_PART};
+use crate::template::stats::Stats;
+use crate::template::submissions::{Outcome, Submissions};
+use crate::template::{aoc_cli, Puzzle, ANSI_ITALIC, ANSI_RESET};
+use crate::template::{Answer, Failure, PartResult, ANSI_BOLD, ANSI_RED};
 
-pub fn run_part<I: Copy, T: Display>(func: impl Fn(I) -> Option<T>, input: I, day: Day, part: u8) {
+/// Default time budget for benchmarking a single part.
+const DEFAULT_BENCH_BUDGET: Duration = Duration::from_secs(1);
+/// Fraction of the budget that is spent on warmup iterations before measuring.
+const WARMUP_DIVISOR: u32 = 10;
+const MIN_BENCH_SAMPLES: usize = 10;
+const MAX_BENCH_SAMPLES: usize = 100_000;
+
+pub const BUDGET_ARG: &str = "--budget";
+
+thread_local! {
+    /// Arguments of a solution that is run in-process, see [`with_args`].
+    static ARGS_OVERRIDE: RefCell<Option<Vec<String>>> = const { RefCell::new(None) };
+}
+
+/// Returns the command-line arguments of the running solution.
+/// These are the process arguments unless the solution is run in-process by [`with_args`].
+pub fn args() -> Vec<String> {
+    ARGS_OVERRIDE
+        .with(|args| args.borrow().clone())
+        .unwrap_or_else(|| env::args().collect())
+}
+
+/// Calls `func` as if the solution was invoked with `args`.
+/// This allows the main binary to run a solution's `main` function without spawning a process.
+pub fn with_args<T>(args: Vec<String>, func: impl FnOnce() -> T) -> T {
+    /// Resets the override, even if `func` panics.
+    struct Reset;
+
+    impl Drop for Reset {
+        fn drop(&mut self) {
+            ARGS_OVERRIDE.with(|args| args.borrow_mut().take());
+        }
+    }
+
+    ARGS_OVERRIDE.with(|current| current.replace(Some(args)));
+    let _reset = Reset;
+    func()
+}
+
+/// Run a solution part, which returns either an `Option` or a `Result` (see [`PartResult`]).
+/// A panic inside the part is caught and reported like an error.
+pub fn run_part<I: Copy, R: PartResult>(func: impl Fn(I) -> R, input: I, puzzle: Puzzle, part: u8) {
     let part_str = format!("Part {part}");
 
-    let (result, duration, samples) =
-        run_timed(func, input, |result| print_result(result, &part_str, ""));
+    let (result, duration, stats, memory) = run_timed(
+        |input| func(input).into_answer(),
+        input,
+        |result| print_result(&normalize(result), &part_str, ""),
+    );
+    let result = result
+        .map(|result| normalize(&result))
+        .unwrap_or_else(|payload| Err(Failure::from_panic(payload.as_ref())));
+
+    print_result(
+        &result,
+        &part_str,
+        &format_duration(&duration, stats.as_ref(), memory.as_ref()),
+    );
+    print_distribution(stats.as_ref());
+
+    let record = Record {
+        day: puzzle.day,
+        part,
+        answer: result.as_ref().ok().map(ToString::to_string),
+        #[allow(clippy::cast_precision_loss)]
+        nanos: stats
+            .as_ref()
+            .map_or(duration.as_nanos() as f64, |s| s.mean),
+        samples: stats.as_ref().map_or(1, |s| s.samples),
+        status: match &result {
+            Ok(_) => Status::Solved,
+            Err(Failure::Unsolved) => Status::Unsolved,
+            Err(failure) => Status::Error {
+                message: failure.to_string(),
+            },
+        },
+        stats,
+        memory,
+    };
+
+    if let Err(e) = record.emit() {
+        eprintln!("Failed to write record: {e}");
+    }
+
+    if let Ok(answer) = &result {
+        submit_result(answer, puzzle, part);
+    }
+}
+
+/// Run the parse step of a solution and return its output, which is then shared by both parts.
+/// If the parse step panics, the panic is reported and then resumed, as the parts can not run without its output.
+pub fn run_parse<'a, P>(func: impl Fn(&'a str) -> P, input: &'a str, puzzle: Puzzle) -> P {
+    let label = "Parse";
+
+    let (parsed, duration, stats, memory) = run_timed(func, input, |_| print!("{label}: ✔"));
+
+    let parsed = parsed.unwrap_or_else(|payload| {
+        let failure = Failure::from_panic(payload.as_ref());
+        print!("\r");
+        println!("{label}: ✖ {ANSI_RED}{failure}{ANSI_RESET}");
+        let record = Record {
+            day: puzzle.day,
+            part: PARSE_PART,
+            answer: None,
+            nanos: 0.0,
+            samples: 1,
+            status: Status::Error {
+                message: failure.to_string(),
+            },
+            stats: None,
+            memory: None,
+        };
+        if let Err(e) = record.emit() {
+            eprintln!("Failed to write record: {e}");
+        }
+        panic::resume_unwind(payload)
+    });
+
+    print!("\r");
+    println!(
+        "{label}: ✔{}",
+        format_duration(&duration, stats.as_ref(), memory.as_ref())
+    );
+    print_distribution(stats.as_ref());
 
-    print_result(&result, &part_str, &format_duration(&duration, samples));
+    let record = Record {
+        day: puzzle.day,
+        part: PARSE_PART,
+        answer: None,
+        #[allow(clippy::cast_precision_loss)]
+        nanos: stats
+            .as_ref()
+            .map_or(duration.as_nanos() as f64, |s| s.mean),
+        samples: stats.as_ref().map_or(1, |s| s.samples),
+        status: Status::Solved,
+        stats,
+        memory,
+    };
 
-    if let Some(result) = result {
-        submit_result(result, day, part);
+    if let Err(e) = record.emit() {
+        eprintln!("Failed to write record: {e}");
     }
+
+    parsed
 }
 
-/// Run a solution part. The behavior differs depending on whether we are running a release or debug build:
-///  1. in debug, the function is executed once.
-///  2. in release, the function is benched (approx. 1 second of execution time or 10 samples, whatever take longer.)
+/// Run a solution part. The behavior differs depending on whether the `--time` flag is passed:
+///  1. without it, the function is executed once.
+///  2. with it, the function is warmed up and then benched for the time budget (default: 1 second, at least 10 samples.)
+///
+/// A panic during the first execution is caught and returned, the function is not benched in that case.
+/// The heap usage of the first execution is measured if the `memory` feature is enabled.
 fn run_timed<I: Copy, T>(
     func: impl Fn(I) -> T,
     input: I,
     hook: impl Fn(&T),
-) -> (T, Duration, u128) {
+) -> (
+    thread::Result<T>,
+    Duration,
+    Option<Stats>,
+    Option<MemoryStats>,
+) {
     let timer = Instant::now();
-    let result = {
+    let (result, memory) = {
         #[cfg(feature = "dhat-heap")]
         let _profiler = dhat::Profiler::new_heap();
 
-        func(input)
+        memory::measure(|| panic::catch_unwind(AssertUnwindSafe(|| func(input))))
     };
     let base_time = timer.elapsed();
 
+    let Ok(result) = result else {
+        return (result, base_time, None, memory);
+    };
+
     hook(&result);
 
-    let run = if std::env::args().any(|x| x == "--time") {
-        bench(func, input, &base_time)
+    if args().iter().any(|x| x == "--time") {
+        let stats = bench(func, input);
+        #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+        let mean = Duration::from_nanos(stats.mean as u64);
+        (Ok(result), mean, Some(stats), memory)
     } else {
-        (base_time, 1)
-    };
-
-    (result, run.0, run.1)
+        (Ok(result), base_time, None, memory)
+    }
 }
 
-fn bench<I: Copy, T>(func: impl Fn(I) -> T, input: I, base_time: &Duration) -> (Duration, u128) {
+fn bench<I: Copy, T>(func: impl Fn(I) -> T, input: I) -> Stats {
     let mut stdout = stdout();
 
     print!(" > {ANSI_ITALIC}benching{ANSI_RESET}");
     let _ = stdout.flush();
 
-    let bench_iterations =
-        (Duration::from_secs(1).as_nanos() / cmp::max(base_time.as_nanos(), 10)).clamp(10, 10000);
+    let budget = get_bench_budget();
 
-    let mut timers: Vec<Duration> = vec![];
-
-    for _ in 0..bench_iterations {
-        let timer = Instant::now();
+    // warm up caches and the branch predictor, runs at least once.
+    let timer = Instant::now();
+    loop {
         black_box(func(black_box(input)));
-        timers.push(timer.elapsed());
+        if timer.elapsed() >= budget / WARMUP_DIVISOR {
+            break;
+        }
     }
 
-    (
-        #[allow(clippy::cast_possible_truncation)]
-        Duration::from_nanos(average_duration(&timers) as u64),
-        bench_iterations,
-    )
+    // hardware counters are only enabled while measuring, if the `perf` feature is enabled.
+    let (timers, counters) = perf::count(|| {
+        let mut timers: Vec<Duration> = vec![];
+        let timer = Instant::now();
+
+        while timers.len() < MAX_BENCH_SAMPLES
+            && (timers.len() < MIN_BENCH_SAMPLES || timer.elapsed() < budget)
+        {
+            let timer = Instant::now();
+            black_box(func(black_box(input)));
+            timers.push(timer.elapsed());
+        }
+
+        let samples = timers.len();
+        (timers, samples)
+    });
+
+    // NOTE: there are always at least `MIN_BENCH_SAMPLES` samples.
+    let mut stats = Stats::from_samples(&timers).unwrap();
+    stats.counters = counters;
+    stats
 }
 
-fn average_duration(numbers: &[Duration]) -> u128 {
-    numbers
-        .iter()
-        .map(std::time::Duration::as_nanos)
-        .sum::<u128>()
-        / numbers.len() as u128
+/// Parse the time budget for benchmarks from `--budget <millis>`.
+fn get_bench_budget() -> Duration {
+    let args = args();
+
+    args.iter()
+        .position(|x| x == BUDGET_ARG)
+        .and_then(|i| args.get(i + 1))
+        .and_then(|x| x.parse().ok())
+        .map_or(DEFAULT_BENCH_BUDGET, Duration::from_millis)
 }
 
-fn format_duration(duration: &Duration, samples: u128) -> String {
-    if samples == 1 {
-        format!(" ({duration:.1?})")
-    } else {
-        format!(" ({duration:.1?} @ {samples} samples)")
+fn format_duration(
+    duration: &Duration,
+    stats: Option<&Stats>,
+    memory: Option<&MemoryStats>,
+) -> String {
+    let time = match stats {
+        None => format!("{duration:.1?}"),
+        Some(stats) => format!("{} @ {} samples", stats.format_spread(), stats.samples),
+    };
+    match memory {
+        None => format!(" ({time})"),
+        Some(memory) => format!(" ({time} · {})", memory.format()),
+    }
+}
+
+fn print_distribution(stats: Option<&Stats>) {
+    if let Some(stats) = stats {
+        println!(
+            "        {ANSI_ITALIC}{}, {} outliers{ANSI_RESET}",
+            stats.format_distribution(),
+            stats.outliers
+        );
+        if let Some(counters) = &stats.counters {
+            println!("        {ANSI_ITALIC}{}{ANSI_RESET}", counters.format());
+        }
     }
 }
 
-fn print_result<T: Display>(result: &Option<T>, part: &str, duration_str: &str) {
+/// Normalizes the answer of a part, see [`Answer::new`].
+fn normalize<T: Display>(result: &Result<T, Failure>) -> Result<Answer, Failure> {
+    result.as_ref().map(Answer::new).map_err(Clone::clone)
+}
+
+fn print_result(result: &Result<Answer, Failure>, part: &str, duration_str: &str) {
     let is_intermediate_result = duration_str.is_empty();
 
     match result {
-        Some(result) => {
-            if result.to_string().contains('\n') {
+        Ok(Answer {
+            value,
+            art: Some(art),
+        }) => {
+            let str = format!("{part}: {ANSI_BOLD}{value}{ANSI_RESET}{duration_str}");
+            if is_intermediate_result {
+                print!("{str}");
+            } else {
+                print!("\r");
+                println!("{str}");
+                println!("{art}");
+            }
+        }
+        Ok(result) => {
+            if result.value.contains('\n') {
                 let str = format!("{part}: ▼ {duration_str}");
                 if is_intermediate_result {
                     print!("{str}");
@@ -114,7 +326,7 @@ fn print_result<T: Display>(result: &Option<T>, part: &str, duration_str: &str)
                 }
             }
         }
-        None => {
+        Err(Failure::Unsolved) => {
             if is_intermediate_result {
                 print!("{part}: ✖");
             } else {
@@ -122,18 +334,30 @@ fn print_result<T: Display>(result: &Option<T>, part: &str, duration_str: &str)
                 println!("{part}: ✖             ");
             }
         }
+        // errors are only printed once, as they may span multiple lines.
+        Err(_) if is_intermediate_result => {}
+        Err(failure) => {
+            let failure = failure.to_string();
+            print!("\r");
+            if failure.contains('\n') {
+                println!("{part}: ✖{duration_str}");
+                println!("{ANSI_RED}{failure}{ANSI_RESET}");
+            } else {
+                println!("{part}: ✖ {ANSI_RED}{failure}{ANSI_RESET}{duration_str}");
+            }
+        }
     }
 }
 
 /// Parse the arguments passed to `solve` and try to submit one part of the solution if:
 ///  1. we are in `--release` mode.
-///  2. aoc-cli is installed.
-fn submit_result<T: Display>(
-    result: T,
-    day: Day,
+///  2. a session cookie is configured.
+fn submit_result(
+    answer: &Answer,
+    puzzle: Puzzle,
     part: u8,
-) -> Option<Result<Output, aoc_cli::AocCommandError>> {
-    let args: Vec<String> = env::args().collect();
+) -> Option<Result<aoc_cli::Verdict, aoc_cli::AocCommandError>> {
+    let args = args();
 
     if !args.contains(&"--submit".into()) {
         return None;
@@ -155,11 +379,124 @@ fn submit_result<T: Display>(
         return None;
     }
 
-    if aoc_cli::check().is_err() {
-        eprintln!("command \"aoc\" not found or not callable. Try running \"cargo install aoc-cli\" to install it.");
+    if let Err(e) = aoc_cli::check() {
+        eprintln!("Cannot submit: {e}");
         process::exit(1);
     }
 
-    println!("Submitting result via aoc-cli...");
-    Some(aoc_cli::submit(day, part, &result.to_string()))
+    let answer = &answer.value;
+
+    if answer.contains('\n') {
+        eprintln!(
+            "Not submitting: the answer spans multiple lines and could not be read as letters."
+        );
+        return None;
+    }
+
+    // files that can not be read would be overwritten below, losing all previous entries.
+    let (mut submissions, mut answers) = match Submissions::read_from_file(puzzle.year)
+        .and_then(|submissions| Ok((submissions, KnownAnswers::read_from_file(puzzle.year)?)))
+    {
+        Ok(files) => files,
+        Err(e) => {
+            eprintln!("Not submitting: {e}");
+            process::exit(1);
+        }
+    };
+
+    if let Err(e) = submissions.validate(puzzle.day, part, answer) {
+        eprintln!("Not submitting {answer}: {e}");
+        return None;
+    }
+
+    println!("Submitting result...");
+    let outcome = aoc_cli::submit(puzzle, part, answer);
+
+    match &outcome {
+        Ok(verdict) => {
+            println!("🎄 {verdict}");
+            if *verdict == aoc_cli::Verdict::Correct {
+                answers.set(puzzle.day, part, answer);
+                if let Err(e) = answers.store_file(puzzle.year) {
+                    eprintln!("Failed to store accepted answer: {e}");
+                }
+            }
+        }
+        Err(e) => eprintln!("Failed to submit result: {e}"),
+    }
+
+    let logged = match &outcome {
+        Ok(verdict) => Some(Outcome::Verdict(*verdict)),
+        Err(aoc_cli::AocCommandError::RateLimited(wait)) => Some(Outcome::RateLimited(*wait)),
+        Err(_) => None,
+    };
+
+    if let Some(logged) = logged {
+        submissions.push(&current_timestamp(), puzzle.day, part, answer, logged);
+        if let Err(e) = submissions.store_file(puzzle.year) {
+            eprintln!("Failed to store submission: {e}");
+        }
+    }
+
+    Some(outcome)
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::{args, run_parse, run_part, with_args};
+    use crate::{
+        day,
+        template::{
+            record::{read_records, Status, PARSE_PART, RECORDS_ARG},
+            Puzzle,
+        },
+        year,
+    };
+    use std::{env, fs, panic};
+
+    #[test]
+    fn overrides_args_while_running_in_process() {
+        let inner = with_args(vec!["2024-01".into(), "--time".into()], args);
+        assert_eq!(inner, vec!["2024-01", "--time"]);
+        assert_ne!(args(), inner);
+    }
+
+    #[test]
+    fn resets_args_after_panics() {
+        let result = panic::catch_unwind(|| with_args(vec!["2024-01".into()], || panic!()));
+        assert!(result.is_err());
+        assert_ne!(args(), vec!["2024-01"]);
+    }
+
+    #[test]
+    fn shares_the_parsed_input_between_parts() {
+        let path = env::temp_dir().join(format!("aoc-runner-{}.jsonl", std::process::id()));
+        let args = vec![
+            "2024-01".into(),
+            RECORDS_ARG.into(),
+            path.display().to_string(),
+        ];
+        let puzzle = Puzzle::new(year!(2024), day!(1));
+
+        with_args(args, || {
+            let parsed = run_parse(
+                |input: &str| input.lines().map(str::len).collect::<Vec<_>>(),
+                "ab\ncde",
+                puzzle,
+            );
+            run_part(|x: &Vec<usize>| x.iter().max().copied(), &parsed, puzzle, 1);
+            run_part(|x: &Vec<usize>| x.get(5).copied(), &parsed, puzzle, 2);
+        });
+
+        let records = read_records(&path).unwrap();
+        let _ = fs::remove_file(&path);
+
+        assert_eq!(records.len(), 3);
+        assert_eq!(records[0].part, PARSE_PART);
+        assert_eq!(records[0].status, Status::Solved);
+        assert_eq!(records[1].answer.as_deref(), Some("3"));
+        assert_eq!(records[2].status, Status::Unsolved);
+    }
 }
@@ -0,0 +1,235 @@
+/// Summary statistics for benchmark samples.
+use std::{collections::HashMap, time::Duration};
+
+use tinyjson::JsonValue;
+
+use crate::template::perf::Counters;
+
+/// Represents the distribution of a set of benchmark samples. All values are in nanoseconds.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Stats {
+    pub mean: f64,
+    pub min: f64,
+    pub max: f64,
+    pub median: f64,
+    pub p95: f64,
+    pub std_dev: f64,
+    pub samples: u128,
+    /// Number of samples outside of the Tukey fences (1.5 times the interquartile range).
+    pub outliers: u128,
+    /// Hardware counters per sample, only present if the `perf` feature is enabled and the counters are available.
+    pub counters: Option<Counters>,
+}
+
+impl Stats {
+    /// Computes statistics for a set of samples. Returns [`None`] if there are no samples.
+    pub fn from_samples(samples: &[Duration]) -> Option<Self> {
+        if samples.is_empty() {
+            return None;
+        }
+
+        #[allow(clippy::cast_precision_loss)]
+        let mut nanos: Vec<f64> = samples.iter().map(|d| d.as_nanos() as f64).collect();
+        nanos.sort_unstable_by(f64::total_cmp);
+
+        #[allow(clippy::cast_precision_loss)]
+        let len = nanos.len() as f64;
+        let mean = nanos.iter().sum::<f64>() / len;
+
+        let variance = if nanos.len() > 1 {
+            nanos.iter().map(|x| (x - mean).powi(2)).sum::<f64>() / (len - 1.0)
+        } else {
+            0.0
+        };
+
+        let q1 = percentile(&nanos, 25.0);
+        let q3 = percentile(&nanos, 75.0);
+        let iqr = q3 - q1;
+        let (lower_fence, upper_fence) = (q1 - 1.5 * iqr, q3 + 1.5 * iqr);
+
+        Some(Self {
+            mean,
+            min: nanos[0],
+            max: nanos[nanos.len() - 1],
+            median: percentile(&nanos, 50.0),
+            p95: percentile(&nanos, 95.0),
+            std_dev: variance.sqrt(),
+            samples: nanos.len() as u128,
+            outliers: nanos
+                .iter()
+                .filter(|x| **x < lower_fence || **x > upper_fence)
+                .count() as u128,
+            counters: None,
+        })
+    }
+
+    /// Formats the mean and standard deviation, e.g. `74.1ns ± 3.2ns`.
+    pub fn format_spread(&self) -> String {
+        format!(
+            "{} ± {}",
+            format_nanos(self.mean),
+            format_nanos(self.std_dev)
+        )
+    }
+
+    /// Formats the shape of the distribution, e.g. `min 70.0ns · median 73.9ns · p95 80.1ns · max 120.3ns`.
+    pub fn format_distribution(&self) -> String {
+        format!(
+            "min {} · median {} · p95 {} · max {}",
+            format_nanos(self.min),
+            format_nanos(self.median),
+            format_nanos(self.p95),
+            format_nanos(self.max)
+        )
+    }
+}
+
+/// Linear interpolation between closest ranks. Expects sorted, non-empty input.
+fn percentile(sorted: &[f64], p: f64) -> f64 {
+    #[allow(clippy::cast_precision_loss)]
+    let rank = p / 100.0 * (sorted.len() - 1) as f64;
+
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+    let (lower, upper) = (rank.floor() as usize, rank.ceil() as usize);
+
+    sorted[lower] + (sorted[upper] - sorted[lower]) * (rank - rank.floor())
+}
+
+/// Formats a number of nanoseconds like a [`Duration`], e.g. `74.1ns` or `1.2ms`.
+pub fn format_nanos(nanos: f64) -> String {
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+    let duration = Duration::from_nanos(nanos.round() as u64);
+    format!("{duration:.1?}")
+}
+
+/// Parses a duration formatted by [`format_nanos`] back into nanoseconds, e.g. `1.2ms`.
+pub fn parse_nanos(s: &str) -> Option<f64> {
+    // for possible time formats, see: https://github.com/rust-lang/rust/blob/1.64.0/library/core/src/time.rs#L1176-L1200
+    let s = s.trim();
+    let (value, factor) = if let Some(x) = s.strip_suffix("ns") {
+        (x, 1_f64)
+    } else if let Some(x) = s.strip_suffix("µs") {
+        (x, 1_000_f64)
+    } else if let Some(x) = s.strip_suffix("ms") {
+        (x, 1_000_000_f64)
+    } else {
+        (s.strip_suffix('s')?, 1_000_000_000_f64)
+    };
+
+    value.parse::<f64>().ok().map(|x| x * factor)
+}
+
+/* -------------------------------------------------------------------------- */
+
+impl From<&Stats> for JsonValue {
+    fn from(value: &Stats) -> Self {
+        let mut map: HashMap<String, JsonValue> = HashMap::new();
+
+        map.insert("mean".into(), JsonValue::Number(value.mean));
+        map.insert("min".into(), JsonValue::Number(value.min));
+        map.insert("max".into(), JsonValue::Number(value.max));
+        map.insert("median".into(), JsonValue::Number(value.median));
+        map.insert("p95".into(), JsonValue::Number(value.p95));
+        map.insert("std_dev".into(), JsonValue::Number(value.std_dev));
+        #[allow(clippy::cast_precision_loss)]
+        map.insert("samples".into(), JsonValue::Number(value.samples as f64));
+        #[allow(clippy::cast_precision_loss)]
+        map.insert("outliers".into(), JsonValue::Number(value.outliers as f64));
+        if let Some(counters) = &value.counters {
+            map.insert("counters".into(), JsonValue::from(counters));
+        }
+
+        JsonValue::Object(map)
+    }
+}
+
+impl TryFrom<&JsonValue> for Stats {
+    type Error = String;
+
+    fn try_from(value: &JsonValue) -> Result<Self, Self::Error> {
+        let json = value
+            .get::<HashMap<String, JsonValue>>()
+            .ok_or("Expected stats to be a JSON object.")?;
+
+        let number = |key: &str| {
+            json.get(key)
+                .and_then(|v| v.get::<f64>().copied())
+                .ok_or(format!("Expected stats.{key} to be a number."))
+        };
+
+        #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+        Ok(Stats {
+            mean: number("mean")?,
+            min: number("min")?,
+            max: number("max")?,
+            median: number("median")?,
+            p95: number("p95")?,
+            std_dev: number("std_dev")?,
+            samples: number("samples")? as u128,
+            outliers: number("outliers")? as u128,
+            counters: match json.get("counters") {
+                Some(v) if !v.is_null() => Some(Counters::try_from(v)?),
+                _ => None,
+            },
+        })
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::Stats;
+    use std::time::Duration;
+
+    fn samples(nanos: &[u64]) -> Vec<Duration> {
+        nanos.iter().map(|x| Duration::from_nanos(*x)).collect()
+    }
+
+    #[test]
+    fn handles_empty_samples() {
+        assert_eq!(Stats::from_samples(&[]), None);
+    }
+
+    #[test]
+    fn computes_stats() {
+        let stats = Stats::from_samples(&samples(&[5, 1, 4, 2, 3])).unwrap();
+        assert_eq!(stats.mean, 3.0);
+        assert_eq!(stats.min, 1.0);
+        assert_eq!(stats.max, 5.0);
+        assert_eq!(stats.median, 3.0);
+        assert_eq!(stats.p95, 4.8);
+        assert!((stats.std_dev - 1.581_138_83).abs() < 1e-6);
+        assert_eq!(stats.samples, 5);
+        assert_eq!(stats.outliers, 0);
+    }
+
+    #[test]
+    fn detects_outliers() {
+        let stats = Stats::from_samples(&samples(&[10, 11, 10, 12, 11, 10, 500])).unwrap();
+        assert_eq!(stats.outliers, 1);
+        assert_eq!(stats.median, 11.0);
+    }
+
+    #[test]
+    fn handles_single_sample() {
+        let stats = Stats::from_samples(&samples(&[42])).unwrap();
+        assert_eq!(stats.std_dev, 0.0);
+        assert_eq!(stats.p95, 42.0);
+    }
+
+    #[test]
+    fn parses_formatted_nanos() {
+        assert_eq!(super::parse_nanos("74.13ns"), Some(74.13));
+        assert_eq!(super::parse_nanos("1.5µs"), Some(1_500.0));
+        assert_eq!(super::parse_nanos("2ms"), Some(2_000_000.0));
+        assert_eq!(super::parse_nanos("2.0s"), Some(2_000_000_000.0));
+        assert_eq!(super::parse_nanos("-"), None);
+    }
+
+    #[test]
+    fn formats_spread() {
+        let stats = Stats::from_samples(&samples(&[1_000, 3_000])).unwrap();
+        assert_eq!(stats.format_spread(), "2.0µs ± 1.4µs");
+    }
+}
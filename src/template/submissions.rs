@@ -0,0 +1,399 @@
+/// Module that keeps a local log of submitted answers.
+/// The log is used to refuse answers that are already known to be wrong before contacting the server.
+use std::{
+    collections::HashMap,
+    fmt::Display,
+    fs,
+    io::{Error, ErrorKind},
+    path::Path,
+    str::FromStr,
+    time::Duration,
+};
+
+use tinyjson::JsonValue;
+
+use crate::template::{aoc_cli::Verdict, create_data_file, Day, Year};
+
+static SUBMISSIONS_FILE_NAME: &str = "submissions.json";
+
+/// Outcome of a submission as reported by the server.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Outcome {
+    Verdict(Verdict),
+    RateLimited(Option<Duration>),
+}
+
+impl Outcome {
+    fn name(&self) -> &'static str {
+        match self {
+            Outcome::Verdict(Verdict::Correct) => "correct",
+            Outcome::Verdict(Verdict::TooHigh) => "too_high",
+            Outcome::Verdict(Verdict::TooLow) => "too_low",
+            Outcome::Verdict(Verdict::Incorrect) => "incorrect",
+            Outcome::Verdict(Verdict::AlreadySolved) => "already_solved",
+            Outcome::RateLimited(_) => "rate_limited",
+        }
+    }
+
+    fn from_name(name: &str, wait: Option<Duration>) -> Option<Self> {
+        Some(match name {
+            "correct" => Outcome::Verdict(Verdict::Correct),
+            "too_high" => Outcome::Verdict(Verdict::TooHigh),
+            "too_low" => Outcome::Verdict(Verdict::TooLow),
+            "incorrect" => Outcome::Verdict(Verdict::Incorrect),
+            "already_solved" => Outcome::Verdict(Verdict::AlreadySolved),
+            "rate_limited" => Outcome::RateLimited(wait),
+            _ => return None,
+        })
+    }
+
+    fn is_wrong(&self) -> bool {
+        matches!(
+            self,
+            Outcome::Verdict(Verdict::TooHigh | Verdict::TooLow | Verdict::Incorrect)
+        )
+    }
+}
+
+/// A single submitted answer.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Submission {
+    /// UTC timestamp in RFC 3339 format, e.g. `2024-12-06T08:30:00Z`.
+    pub timestamp: String,
+    pub day: Day,
+    pub part: u8,
+    pub answer: String,
+    pub outcome: Outcome,
+}
+
+/// Reason for refusing to submit an answer.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Rejection {
+    /// The same answer was submitted before and was wrong.
+    KnownWrong(Verdict),
+    /// The answer is not lower than an answer that was too high.
+    AboveBound(String),
+    /// The answer is not higher than an answer that was too low.
+    BelowBound(String),
+}
+
+impl Display for Rejection {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Rejection::KnownWrong(verdict) => {
+                write!(f, "this answer was submitted before. {verdict}")
+            }
+            Rejection::AboveBound(bound) => {
+                write!(
+                    f,
+                    "the answer must be lower than {bound}, which was too high."
+                )
+            }
+            Rejection::BelowBound(bound) => {
+                write!(
+                    f,
+                    "the answer must be higher than {bound}, which was too low."
+                )
+            }
+        }
+    }
+}
+
+/// Represents all submitted answers.
+/// Can be serialized from / to JSON.
+#[derive(Clone, Debug, Default)]
+pub struct Submissions {
+    pub entries: Vec<Submission>,
+}
+
+impl Submissions {
+    /// Dehydrate submissions to a JSON file.
+    pub fn store_file(&self, year: Year) -> Result<(), Error> {
+        let json = JsonValue::from(self);
+        let mut file = create_data_file(year, SUBMISSIONS_FILE_NAME)?;
+        json.format_to(&mut file)
+    }
+
+    /// Rehydrate submissions from a JSON file. If not present, returns no submissions.
+    /// Fails if the file can not be read or parsed, so that previous wrong answers are not lost by overwriting it.
+    pub fn read_from_file(year: Year) -> Result<Self, String> {
+        Self::read_from_path(&year.data_dir().join(SUBMISSIONS_FILE_NAME))
+    }
+
+    fn read_from_path(path: &Path) -> Result<Self, String> {
+        match fs::read_to_string(path) {
+            Ok(content) => Submissions::try_from(content)
+                .map_err(|e| format!("could not parse `{}`: {e}", path.display())),
+            Err(e) if e.kind() == ErrorKind::NotFound => Ok(Submissions::default()),
+            Err(e) => Err(format!("could not read `{}`: {e}", path.display())),
+        }
+    }
+
+    /// Returns all submissions of a part in chronological order.
+    pub fn for_part(&self, day: Day, part: u8) -> impl Iterator<Item = &Submission> {
+        self.entries
+            .iter()
+            .filter(move |s| s.day == day && s.part == part)
+    }
+
+    /// Checks an answer against previous submissions of the same part.
+    pub fn validate(&self, day: Day, part: u8, answer: &str) -> Result<(), Rejection> {
+        let answer = answer.trim();
+
+        if let Some(previous) = self
+            .for_part(day, part)
+            .find(|s| s.answer == answer && s.outcome.is_wrong())
+        {
+            if let Outcome::Verdict(verdict) = previous.outcome {
+                return Err(Rejection::KnownWrong(verdict));
+            }
+        }
+
+        let Ok(value) = answer.parse::<i128>() else {
+            return Ok(());
+        };
+
+        let bound = |verdict: Verdict| {
+            self.for_part(day, part)
+                .filter(move |s| s.outcome == Outcome::Verdict(verdict))
+                .filter_map(|s| s.answer.parse::<i128>().ok())
+        };
+
+        if let Some(upper) = bound(Verdict::TooHigh).min() {
+            if value >= upper {
+                return Err(Rejection::AboveBound(upper.to_string()));
+            }
+        }
+
+        if let Some(lower) = bound(Verdict::TooLow).max() {
+            if value <= lower {
+                return Err(Rejection::BelowBound(lower.to_string()));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Appends a submission.
+    pub fn push(&mut self, timestamp: &str, day: Day, part: u8, answer: &str, outcome: Outcome) {
+        self.entries.push(Submission {
+            timestamp: timestamp.into(),
+            day,
+            part,
+            answer: answer.trim().into(),
+            outcome,
+        });
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+impl From<&Submissions> for JsonValue {
+    fn from(value: &Submissions) -> Self {
+        let mut map: HashMap<String, JsonValue> = HashMap::new();
+
+        map.insert(
+            "entries".into(),
+            JsonValue::Array(value.entries.iter().map(JsonValue::from).collect()),
+        );
+
+        JsonValue::Object(map)
+    }
+}
+
+impl TryFrom<String> for Submissions {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        let json = JsonValue::from_str(&value).or(Err("not valid JSON file."))?;
+
+        let json_entries = json
+            .get::<HashMap<String, JsonValue>>()
+            .ok_or("expected JSON document to be an object.")?
+            .get("entries")
+            .ok_or("expected JSON document to have key `entries`.")?
+            .get::<Vec<JsonValue>>()
+            .ok_or("expected `json.entries` to be an array.")?;
+
+        Ok(Submissions {
+            entries: json_entries
+                .iter()
+                .map(Submission::try_from)
+                .collect::<Result<_, _>>()?,
+        })
+    }
+}
+
+impl From<&Submission> for JsonValue {
+    fn from(value: &Submission) -> Self {
+        let mut map: HashMap<String, JsonValue> = HashMap::new();
+
+        map.insert(
+            "timestamp".into(),
+            JsonValue::String(value.timestamp.clone()),
+        );
+        map.insert("day".into(), JsonValue::String(value.day.to_string()));
+        map.insert("part".into(), JsonValue::Number(f64::from(value.part)));
+        map.insert("answer".into(), JsonValue::String(value.answer.clone()));
+        map.insert(
+            "verdict".into(),
+            JsonValue::String(value.outcome.name().into()),
+        );
+        #[allow(clippy::cast_precision_loss)]
+        map.insert(
+            "wait_seconds".into(),
+            match value.outcome {
+                Outcome::RateLimited(Some(wait)) => JsonValue::Number(wait.as_secs() as f64),
+                _ => JsonValue::Null,
+            },
+        );
+
+        JsonValue::Object(map)
+    }
+}
+
+impl TryFrom<&JsonValue> for Submission {
+    type Error = String;
+
+    fn try_from(value: &JsonValue) -> Result<Self, Self::Error> {
+        let json = value
+            .get::<HashMap<String, JsonValue>>()
+            .ok_or("Expected submission to be a JSON object.")?;
+
+        let string = |key: &str| {
+            json.get(key)
+                .and_then(|v| v.get::<String>())
+                .cloned()
+                .ok_or(format!("Expected submission.{key} to be a string."))
+        };
+
+        let day = Day::from_str(&string("day")?)
+            .map_err(|_| "Expected submission.day to be a Day struct.")?;
+
+        #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+        let part = json
+            .get("part")
+            .and_then(|v| v.get::<f64>())
+            .map(|x| *x as u8)
+            .ok_or("Expected submission.part to be a number.")?;
+
+        #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+        let wait = json
+            .get("wait_seconds")
+            .and_then(|v| v.get::<f64>())
+            .map(|x| Duration::from_secs(*x as u64));
+
+        let outcome = Outcome::from_name(&string("verdict")?, wait)
+            .ok_or("Expected submission.verdict to be a known verdict.")?;
+
+        Ok(Submission {
+            timestamp: string("timestamp")?,
+            day,
+            part,
+            answer: string("answer")?,
+            outcome,
+        })
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::{Outcome, Rejection, Submissions};
+    use crate::{day, template::aoc_cli::Verdict};
+    use std::{env, fs, time::Duration};
+    use tinyjson::JsonValue;
+
+    fn get_mock_submissions() -> Submissions {
+        let mut submissions = Submissions::default();
+        let timestamp = "2024-12-06T08:30:00Z";
+        submissions.push(
+            timestamp,
+            day!(6),
+            1,
+            "100",
+            Outcome::Verdict(Verdict::TooHigh),
+        );
+        submissions.push(
+            timestamp,
+            day!(6),
+            1,
+            "20",
+            Outcome::Verdict(Verdict::TooLow),
+        );
+        submissions.push(
+            timestamp,
+            day!(6),
+            1,
+            "50",
+            Outcome::Verdict(Verdict::Incorrect),
+        );
+        submissions.push(timestamp, day!(6), 1, "60", Outcome::RateLimited(None));
+        submissions
+    }
+
+    #[test]
+    fn refuses_known_wrong_answers() {
+        let submissions = get_mock_submissions();
+        assert_eq!(
+            submissions.validate(day!(6), 1, "50"),
+            Err(Rejection::KnownWrong(Verdict::Incorrect))
+        );
+        assert_eq!(
+            submissions.validate(day!(6), 1, "100"),
+            Err(Rejection::KnownWrong(Verdict::TooHigh))
+        );
+        assert_eq!(submissions.validate(day!(6), 1, "60"), Ok(()));
+    }
+
+    #[test]
+    fn refuses_answers_outside_bounds() {
+        let submissions = get_mock_submissions();
+        assert_eq!(
+            submissions.validate(day!(6), 1, "150"),
+            Err(Rejection::AboveBound("100".into()))
+        );
+        assert_eq!(
+            submissions.validate(day!(6), 1, "-3"),
+            Err(Rejection::BelowBound("20".into()))
+        );
+        assert_eq!(submissions.validate(day!(6), 1, "42"), Ok(()));
+    }
+
+    #[test]
+    fn scopes_validation_to_part() {
+        let submissions = get_mock_submissions();
+        assert_eq!(submissions.validate(day!(6), 2, "150"), Ok(()));
+        assert_eq!(submissions.validate(day!(7), 1, "50"), Ok(()));
+        assert_eq!(submissions.validate(day!(6), 1, "abc"), Ok(()));
+    }
+
+    #[test]
+    fn roundtrips_submissions() {
+        let mut submissions = get_mock_submissions();
+        submissions.push(
+            "2024-12-06T08:31:00Z",
+            day!(6),
+            2,
+            "7",
+            Outcome::RateLimited(Some(Duration::from_secs(65))),
+        );
+
+        let json = JsonValue::from(&submissions).stringify().unwrap();
+        let parsed = Submissions::try_from(json).unwrap();
+
+        assert_eq!(parsed.entries, submissions.entries);
+    }
+
+    #[test]
+    fn refuses_to_read_corrupt_files() {
+        let path = env::temp_dir().join(format!("aoc-submissions-{}.json", std::process::id()));
+        assert_eq!(Submissions::read_from_path(&path).unwrap().entries.len(), 0);
+
+        fs::write(&path, "{ \"entries\": [").unwrap();
+        let error = Submissions::read_from_path(&path).unwrap_err();
+        let _ = fs::remove_file(&path);
+        assert!(error.starts_with("could not parse"));
+    }
+}
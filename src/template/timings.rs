@@ -1,19 +1,69 @@
 use std::{collections::HashMap, fs, io::Error, str::FromStr};
 use tinyjson::JsonValue;
 
-use crate::template::Day;
+use crate::template::{
+    create_data_file,
+    memory::MemoryStats,
+    record::PARSE_PART,
+    stats::{parse_nanos, Stats},
+    Day, Year,
+};
 
-static TIMINGS_FILE_PATH: &str = "./data/timings.json";
+static TIMINGS_FILE_NAME: &str = "timings.json";
 
 /// Represents benchmark times for a single day.
 #[derive(Clone, Debug)]
 pub struct Timing {
     pub day: Day,
+    /// Timing of the optional parse step that is shared by both parts.
+    pub parse: Option<String>,
     pub part_1: Option<String>,
     pub part_2: Option<String>,
+    pub parse_stats: Option<Stats>,
+    pub part_1_stats: Option<Stats>,
+    pub part_2_stats: Option<Stats>,
+    /// Heap usage of the parse step and the parts, only present if the `memory` feature is enabled.
+    pub parse_memory: Option<MemoryStats>,
+    pub part_1_memory: Option<MemoryStats>,
+    pub part_2_memory: Option<MemoryStats>,
     pub total_nanos: f64,
 }
 
+impl Timing {
+    /// Creates a timing for a day that has no parts yet.
+    pub fn new(day: Day) -> Self {
+        Self {
+            day,
+            parse: None,
+            part_1: None,
+            part_2: None,
+            parse_stats: None,
+            part_1_stats: None,
+            part_2_stats: None,
+            parse_memory: None,
+            part_1_memory: None,
+            part_2_memory: None,
+            total_nanos: 0.0,
+        }
+    }
+
+    /// Returns the mean time of a part in nanoseconds. Part `0` refers to the parse step.
+    /// Falls back to the formatted timing for entries that were stored without stats.
+    pub fn part_nanos(&self, part: u8) -> Option<f64> {
+        let (timing, stats) = match part {
+            PARSE_PART => (&self.parse, &self.parse_stats),
+            1 => (&self.part_1, &self.part_1_stats),
+            2 => (&self.part_2, &self.part_2_stats),
+            _ => return None,
+        };
+
+        stats
+            .as_ref()
+            .map(|s| s.mean)
+            .or_else(|| timing.as_deref().and_then(parse_nanos))
+    }
+}
+
 /// Represents benchmark times for a set of days.
 /// Can be serialized from / to JSON.
 #[derive(Clone, Debug, Default)]
@@ -23,15 +73,15 @@ pub struct Timings {
 
 impl Timings {
     /// Dehydrate timings to a JSON file.
-    pub fn store_file(&self) -> Result<(), Error> {
+    pub fn store_file(&self, year: Year) -> Result<(), Error> {
         let json = JsonValue::from(self.clone());
-        let mut file = fs::File::create(TIMINGS_FILE_PATH)?;
+        let mut file = create_data_file(year, TIMINGS_FILE_NAME)?;
         json.format_to(&mut file)
     }
 
     /// Rehydrate timings from a JSON file. If not present, returns empty timings.
-    pub fn read_from_file() -> Self {
-        fs::read_to_string(TIMINGS_FILE_PATH)
+    pub fn read_from_file(year: Year) -> Self {
+        fs::read_to_string(year.data_dir().join(TIMINGS_FILE_NAME))
             .map_err(|x| x.to_string())
             .and_then(Timings::try_from)
             .unwrap_or_default()
@@ -51,7 +101,7 @@ impl Timings {
             }
         }
 
-        data.sort_unstable_by(|a, b| a.day.cmp(&b.day));
+        data.sort_unstable_by_key(|t| t.day);
         Timings { data }
     }
 
@@ -60,10 +110,10 @@ impl Timings {
         self.data.iter().map(|x| x.total_nanos).sum::<f64>() / 1_000_000_f64
     }
 
-    pub fn is_day_complete(&self, day: Day) -> bool {
+    pub fn is_day_complete(&self, day: &Day) -> bool {
         self.data
             .iter()
-            .any(|t| t.day == day && t.part_1.is_some() && t.part_2.is_some())
+            .any(|t| t.day == *day && t.part_1.is_some() && t.part_2.is_some())
     }
 }
 
@@ -114,6 +164,10 @@ impl From<&Timing> for JsonValue {
         map.insert("day".into(), JsonValue::String(value.day.to_string()));
         map.insert("total_nanos".into(), JsonValue::Number(value.total_nanos));
 
+        if let Some(parse) = &value.parse {
+            map.insert("parse".into(), JsonValue::String(parse.clone()));
+        }
+
         let part_1 = value.part_1.clone().map(JsonValue::String);
         let part_2 = value.part_2.clone().map(JsonValue::String);
 
@@ -133,6 +187,26 @@ impl From<&Timing> for JsonValue {
             },
         );
 
+        for (key, stats) in [
+            ("parse_stats", &value.parse_stats),
+            ("part_1_stats", &value.part_1_stats),
+            ("part_2_stats", &value.part_2_stats),
+        ] {
+            if let Some(stats) = stats {
+                map.insert(key.into(), JsonValue::from(stats));
+            }
+        }
+
+        for (key, memory) in [
+            ("parse_memory", &value.parse_memory),
+            ("part_1_memory", &value.part_1_memory),
+            ("part_2_memory", &value.part_2_memory),
+        ] {
+            if let Some(memory) = memory {
+                map.insert(key.into(), JsonValue::from(memory));
+            }
+        }
+
         JsonValue::Object(map)
     }
 }
@@ -151,6 +225,15 @@ impl TryFrom<&JsonValue> for Timing {
             .and_then(|day| Day::from_str(day).ok())
             .ok_or("Expected timing.day to be a Day struct.")?;
 
+        let parse = match json.get("parse") {
+            Some(v) if !v.is_null() => Some(
+                v.get::<String>()
+                    .ok_or("Expected timing.parse to be null or string.")?
+                    .clone(),
+            ),
+            _ => None,
+        };
+
         let part_1 = json
             .get("part_1")
             .map(|v| if v.is_null() { None } else { v.get::<String>() })
@@ -166,10 +249,27 @@ impl TryFrom<&JsonValue> for Timing {
             .and_then(|v| v.get::<f64>().copied())
             .ok_or("Expected timing.total_nanos to be a number.")?;
 
+        // NOTE: parse, stats and memory are optional to stay compatible with timings stored by older versions.
+        let get_stats = |key: &str| match json.get(key) {
+            Some(v) if !v.is_null() => Stats::try_from(v).map(Some),
+            _ => Ok(None),
+        };
+        let get_memory = |key: &str| match json.get(key) {
+            Some(v) if !v.is_null() => MemoryStats::try_from(v).map(Some),
+            _ => Ok(None),
+        };
+
         Ok(Timing {
             day,
+            parse,
             part_1: part_1.cloned(),
             part_2: part_2.cloned(),
+            parse_stats: get_stats("parse_stats")?,
+            part_1_stats: get_stats("part_1_stats")?,
+            part_2_stats: get_stats("part_2_stats")?,
+            parse_memory: get_memory("parse_memory")?,
+            part_1_memory: get_memory("part_1_memory")?,
+            part_2_memory: get_memory("part_2_memory")?,
             total_nanos,
         })
     }
@@ -187,22 +287,21 @@ mod tests {
         Timings {
             data: vec![
                 Timing {
-                    day: day!(1),
                     part_1: Some("10ms".into()),
                     part_2: Some("20ms".into()),
                     total_nanos: 3e+10,
+                    ..Timing::new(day!(1))
                 },
                 Timing {
-                    day: day!(2),
                     part_1: Some("30ms".into()),
                     part_2: Some("40ms".into()),
                     total_nanos: 7e+10,
+                    ..Timing::new(day!(2))
                 },
                 Timing {
-                    day: day!(4),
                     part_1: Some("40ms".into()),
-                    part_2: None,
                     total_nanos: 4e+10,
+                    ..Timing::new(day!(4))
                 },
             ],
         }
@@ -223,6 +322,26 @@ mod tests {
             assert_eq!(timing.total_nanos, 1_000_000_000_f64);
         }
 
+        #[test]
+        fn handles_json_timings_with_stats() {
+            let json = r#"{ "data": [{ "day": "01", "part_1": "1.0ms", "part_2": null, "total_nanos": 1000000, "part_1_stats": { "mean": 1000000, "min": 900000, "max": 1200000, "median": 1000000, "p95": 1100000, "std_dev": 50000, "samples": 1000, "outliers": 3 } }] }"#.to_string();
+            let timings = Timings::try_from(json).unwrap();
+            let timing = timings.data.first().unwrap();
+            let stats = timing.part_1_stats.as_ref().unwrap();
+            assert_eq!(stats.std_dev, 50_000_f64);
+            assert_eq!(stats.outliers, 3);
+            assert_eq!(timing.part_2_stats, None);
+        }
+
+        #[test]
+        fn handles_json_timings_with_parse() {
+            let json = r#"{ "data": [{ "day": "06", "parse": "2.0ms", "part_1": "1ms", "part_2": "3ms", "total_nanos": 6000000 }] }"#.to_string();
+            let timings = Timings::try_from(json).unwrap();
+            let timing = timings.data.first().unwrap();
+            assert_eq!(timing.parse, Some("2.0ms".to_string()));
+            assert_eq!(timing.parse_stats, None);
+        }
+
         #[test]
         fn handles_empty_timings() {
             let json = r#"{ "data": [] }"#.to_string();
@@ -278,10 +397,10 @@ mod tests {
         fn handles_completed_days() {
             let timings = Timings {
                 data: vec![Timing {
-                    day: day!(1),
                     part_1: Some("1ms".into()),
                     part_2: Some("2ms".into()),
                     total_nanos: 3_000_000_000_f64,
+                    ..Timing::new(day!(1))
                 }],
             };
 
@@ -292,10 +411,9 @@ mod tests {
         fn handles_partial_days() {
             let timings = Timings {
                 data: vec![Timing {
-                    day: day!(1),
                     part_1: Some("1ms".into()),
-                    part_2: None,
                     total_nanos: 1_000_000_000_f64,
+                    ..Timing::new(day!(1))
                 }],
             };
 
@@ -305,12 +423,7 @@ mod tests {
         #[test]
         fn handles_uncompleted_days() {
             let timings = Timings {
-                data: vec![Timing {
-                    day: day!(1),
-                    part_1: None,
-                    part_2: None,
-                    total_nanos: 0.0,
-                }],
+                data: vec![Timing::new(day!(1))],
             };
 
             assert_eq!(timings.is_day_complete(&day!(1)), false);
@@ -329,12 +442,7 @@ mod tests {
         fn handles_disjunct_timings() {
             let timings = get_mock_timings();
             let other = Timings {
-                data: vec![Timing {
-                    day: day!(3),
-                    part_1: None,
-                    part_2: None,
-                    total_nanos: 0_f64,
-                }],
+                data: vec![Timing::new(day!(3))],
             };
             let merged = timings.merge(&other);
             assert_eq!(merged.data.len(), 4);
@@ -349,12 +457,7 @@ mod tests {
             let timings = get_mock_timings();
 
             let other = Timings {
-                data: vec![Timing {
-                    day: day!(2),
-                    part_1: None,
-                    part_2: None,
-                    total_nanos: 0_f64,
-                }],
+                data: vec![Timing::new(day!(2))],
             };
             let merged = timings.merge(&other);
 
@@ -0,0 +1,158 @@
+/// Helpers for `cargo watch-day`: detecting changes to a puzzle's files and summarizing test runs.
+use std::{fs, path::PathBuf, time::SystemTime};
+
+use crate::template::Puzzle;
+
+/// The files of a puzzle that trigger a re-run when they change.
+pub struct WatchedFiles {
+    puzzle: Puzzle,
+    modified: Vec<(PathBuf, Option<SystemTime>)>,
+}
+
+impl WatchedFiles {
+    /// Watches the solution, the examples and the input of `puzzle`.
+    /// Files that do not exist yet are watched as well, creating them counts as a change.
+    pub fn new(puzzle: Puzzle) -> Self {
+        let mut files = Self {
+            puzzle,
+            modified: vec![],
+        };
+        files.modified = files.paths().into_iter().map(|path| (path, None)).collect();
+        files
+    }
+
+    /// Returns whether any file was created, modified or removed since the last call.
+    /// The first call always returns `true` if any of the files exists.
+    pub fn changed(&mut self) -> bool {
+        let modified: Vec<(PathBuf, Option<SystemTime>)> = self
+            .paths()
+            .into_iter()
+            .map(|path| {
+                let modified = fs::metadata(&path).and_then(|m| m.modified()).ok();
+                (path, modified)
+            })
+            .collect();
+
+        let changed = modified != self.modified;
+        self.modified = modified;
+        changed
+    }
+
+    /// The solution, the input and all example files of the puzzle, i.e. `<day>.txt` and `<day>-*.txt`.
+    fn paths(&self) -> Vec<PathBuf> {
+        let mut paths = vec![
+            self.puzzle.bin_path(),
+            self.puzzle.data_path("examples", "txt"),
+            self.puzzle.data_path("inputs", "txt"),
+        ];
+
+        let prefix = format!("{}-", self.puzzle.day);
+        if let Ok(entries) = fs::read_dir(self.puzzle.year.data_dir().join("examples")) {
+            let mut examples: Vec<PathBuf> = entries
+                .filter_map(Result::ok)
+                .map(|entry| entry.path())
+                .filter(|path| {
+                    path.file_name()
+                        .and_then(|name| name.to_str())
+                        .is_some_and(|name| name.starts_with(&prefix) && name.ends_with(".txt"))
+                })
+                .collect();
+
+            examples.sort_unstable();
+            paths.extend(examples);
+        }
+
+        paths
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+/// Summary of the output of `cargo test`.
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct TestSummary {
+    pub passed: usize,
+    pub failed: usize,
+    /// Names of the failed tests.
+    pub failures: Vec<String>,
+}
+
+impl TestSummary {
+    /// Parses the stdout of `cargo test`. Returns [`None`] if no tests ran, e.g. because the build failed.
+    pub fn parse(output: &str) -> Option<Self> {
+        let mut summary: Option<Self> = None;
+
+        for line in output.lines().map(str::trim) {
+            // `cargo test --quiet` prints `name --- FAILED`, a verbose run prints `test name ... FAILED`.
+            if let Some(name) = line.strip_suffix(" --- FAILED").or_else(|| {
+                line.strip_prefix("test ")
+                    .and_then(|x| x.strip_suffix(" ... FAILED"))
+            }) {
+                summary
+                    .get_or_insert_with(Self::default)
+                    .failures
+                    .push(name.into());
+            } else if let Some(result) = line.strip_prefix("test result: ") {
+                let summary = summary.get_or_insert_with(Self::default);
+
+                for count in result.split([';', '.']).map(str::trim) {
+                    if let Some(n) = count.strip_suffix(" passed") {
+                        summary.passed += n.parse::<usize>().ok()?;
+                    } else if let Some(n) = count.strip_suffix(" failed") {
+                        summary.failed += n.parse::<usize>().ok()?;
+                    }
+                }
+            }
+        }
+
+        summary
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::TestSummary;
+
+    #[test]
+    fn parses_passing_test_runs() {
+        let output = "\nrunning 2 tests\n..\ntest result: ok. 2 passed; 0 failed; 0 ignored; 0 measured; 0 filtered out; finished in 0.00s\n";
+        assert_eq!(
+            TestSummary::parse(output),
+            Some(TestSummary {
+                passed: 2,
+                failed: 0,
+                failures: vec![],
+            })
+        );
+    }
+
+    #[test]
+    fn parses_failing_test_runs() {
+        // output of `cargo test --quiet`, as run by `watch-day`.
+        let output = "\nrunning 2 tests\n. 1/2\ntests::test_part_two --- FAILED\n\nfailures:\n\n---- tests::test_part_two stdout ----\n\nthread 'tests::test_part_two' (20125) panicked at src/bin/2024-21.rs:9:11:\nindex out of bounds: the len is 0 but the index is 2\nnote: run with `RUST_BACKTRACE=1` environment variable to display a backtrace\n\n\nfailures:\n    tests::test_part_two\n\ntest result: FAILED. 1 passed; 1 failed; 0 ignored; 0 measured; 0 filtered out; finished in 0.00s\n";
+        assert_eq!(
+            TestSummary::parse(output),
+            Some(TestSummary {
+                passed: 1,
+                failed: 1,
+                failures: vec!["tests::test_part_two".into()],
+            })
+        );
+    }
+
+    #[test]
+    fn parses_verbose_failing_test_runs() {
+        let output = "running 2 tests\ntest tests::test_part_one ... ok\ntest tests::test_part_two ... FAILED\n\nfailures:\n\nfailures:\n    tests::test_part_two\n\ntest result: FAILED. 1 passed; 1 failed; 0 ignored; 0 measured; 0 filtered out; finished in 0.00s\n";
+        assert_eq!(
+            TestSummary::parse(output).unwrap().failures,
+            vec!["tests::test_part_two".to_string()]
+        );
+    }
+
+    #[test]
+    fn returns_none_without_test_results() {
+        assert_eq!(TestSummary::parse("error[E0425]: cannot find value"), None);
+    }
+}
@@ -0,0 +1,235 @@
+use std::env;
+use std::error::Error;
+use std::fmt::Display;
+use std::fs;
+use std::path::PathBuf;
+use std::str::FromStr;
+
+use crate::template::{Day, MAX_DAY};
+
+#[cfg(feature = "today")]
+use chrono::{DateTime, Datelike, FixedOffset, Utc};
+
+#[cfg(feature = "today")]
+const SERVER_UTC_OFFSET: i32 = -5;
+
+/// Returns the current time in the timezone of the Advent of Code server, which releases puzzles at midnight.
+#[cfg(feature = "today")]
+pub(crate) fn server_now() -> Option<DateTime<FixedOffset>> {
+    let offset = FixedOffset::east_opt(SERVER_UTC_OFFSET * 3600)?;
+    Some(Utc::now().with_timezone(&offset))
+}
+
+/// The year of the first Advent of Code event.
+const FIRST_YEAR: u16 = 2015;
+
+/// Starting with this year, events run for 12 days instead of 25.
+const FIRST_SHORT_EVENT: u16 = 2025;
+
+/// A valid year of Advent of Code (i.e. 2015 or later).
+///
+/// # Display
+/// This value displays as a four digit number.
+///
+/// ```
+/// # use advent_of_code::template::Year;
+/// let year = Year::new(2024).unwrap();
+/// assert_eq!(year.to_string(), "2024")
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Year(u16);
+
+impl Year {
+    /// Creates a [`Year`] from the provided value if it's in the valid range,
+    /// returns [`None`] otherwise.
+    pub fn new(year: u16) -> Option<Self> {
+        if !(FIRST_YEAR..=9999).contains(&year) {
+            return None;
+        }
+        Some(Self(year))
+    }
+
+    // Not part of the public API
+    #[doc(hidden)]
+    pub const fn __new_unchecked(year: u16) -> Self {
+        Self(year)
+    }
+
+    /// Parses the year from the module path of a solution, e.g. `2024_01` for the binary `2024-01`
+    /// or `advent_of_code::registry::_2024_01` when it is compiled into the main binary.
+    /// Panics if the last segment does not start with a year, which fails compilation when used in a const context.
+    // Not part of the public API
+    #[doc(hidden)]
+    pub const fn __from_module_path(path: &str) -> Self {
+        let bytes = path.as_bytes();
+
+        let mut start = bytes.len();
+        while start > 0 && bytes[start - 1] != b':' {
+            start -= 1;
+        }
+        // modules can not start with a digit, so the registry prefixes them with `_`.
+        if start < bytes.len() && bytes[start] == b'_' {
+            start += 1;
+        }
+
+        assert!(
+            bytes.len() - start > 5 && bytes[start + 4] == b'_',
+            "solution binaries must be named `<year>-<day>`, e.g. `src/bin/2024-01.rs`"
+        );
+
+        let mut year = 0;
+        let mut i = start;
+        while i < start + 4 {
+            assert!(
+                bytes[i].is_ascii_digit(),
+                "solution binaries must be named `<year>-<day>`, e.g. `src/bin/2024-01.rs`"
+            );
+            year = year * 10 + (bytes[i] - b'0') as u16;
+            i += 1;
+        }
+
+        assert!(year >= FIRST_YEAR, "invalid year in binary name");
+        Self(year)
+    }
+
+    /// Converts the [`Year`] into an [`u16`].
+    pub const fn into_inner(self) -> u16 {
+        self.0
+    }
+
+    /// Returns the number of days of this year's event.
+    pub const fn days(self) -> u8 {
+        if self.0 >= FIRST_SHORT_EVENT {
+            12
+        } else {
+            MAX_DAY
+        }
+    }
+
+    /// Returns whether `day` is part of this year's event.
+    pub const fn has_day(self, day: Day) -> bool {
+        day.into_inner() <= self.days()
+    }
+
+    /// Returns the year configured with the `AOC_YEAR` environment variable.
+    pub fn from_env() -> Option<Self> {
+        env::var("AOC_YEAR").ok()?.parse().ok()
+    }
+
+    /// Returns the directory that holds the data of this year, e.g. `data/2024`.
+    pub fn data_dir(self) -> PathBuf {
+        PathBuf::from("data").join(self.to_string())
+    }
+
+    /// Returns all years that have a data directory, in ascending order.
+    pub fn stored() -> Vec<Self> {
+        let Ok(entries) = fs::read_dir("data") else {
+            return vec![];
+        };
+
+        let mut years: Vec<Self> = entries
+            .filter_map(Result::ok)
+            .filter(|e| e.path().is_dir())
+            .filter_map(|e| e.file_name().to_str()?.parse().ok())
+            .collect();
+
+        years.sort_unstable();
+        years
+    }
+}
+
+#[cfg(feature = "today")]
+impl Year {
+    /// Returns the year of the current event, i.e. the current year during december and the previous year otherwise.
+    pub fn today() -> Option<Self> {
+        let today = server_now()?;
+        let year = if today.month() == 12 {
+            today.year()
+        } else {
+            today.year() - 1
+        };
+        Self::new(u16::try_from(year).ok()?)
+    }
+}
+
+impl Display for Year {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:04}", self.0)
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+impl FromStr for Year {
+    type Err = YearFromStrError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let year = s.parse().map_err(|_| YearFromStrError)?;
+        Self::new(year).ok_or(YearFromStrError)
+    }
+}
+
+/// An error which can be returned when parsing a [`Year`].
+#[derive(Debug)]
+pub struct YearFromStrError;
+
+impl Error for YearFromStrError {}
+
+impl Display for YearFromStrError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "expecting a year of {FIRST_YEAR} or later")
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+/// Creates a [`Year`] value in a const context.
+#[macro_export]
+macro_rules! year {
+    ($year:expr) => {{
+        const _ASSERT: () = assert!(
+            $year >= 2015 && $year <= 9999,
+            concat!("invalid year `", $year, "`, expecting 2015 or later")
+        );
+        $crate::template::Year::__new_unchecked($year)
+    }};
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::Year;
+
+    #[test]
+    fn parses_years() {
+        assert_eq!("2024".parse::<Year>().ok(), Some(Year(2024)));
+        assert!("2014".parse::<Year>().is_err());
+        assert!("24".parse::<Year>().is_err());
+    }
+
+    #[test]
+    fn knows_event_lengths() {
+        assert_eq!(Year(2015).days(), 25);
+        assert_eq!(Year(2024).days(), 25);
+        assert_eq!(Year(2025).days(), 12);
+        assert!(Year(2025).has_day(crate::day!(12)));
+        assert!(!Year(2025).has_day(crate::day!(13)));
+    }
+
+    #[test]
+    fn parses_module_paths() {
+        assert_eq!(Year::__from_module_path("2015_25"), Year(2015));
+        assert_eq!(Year::__from_module_path("2024_01"), Year(2024));
+        assert_eq!(
+            Year::__from_module_path("advent_of_code::registry::_2024_01"),
+            Year(2024)
+        );
+    }
+
+    #[test]
+    #[should_panic]
+    fn panics_for_module_paths_without_year() {
+        Year::__from_module_path("01");
+    }
+}
@@ -0,0 +1,285 @@
+//! A minimal animated GIF encoder. All frames share one palette of at most 256 colors.
+use std::collections::HashMap;
+
+use super::{Color, Frame};
+
+const MAX_CODE: u16 = 4095;
+
+/// Encodes frames as a looping GIF, with every cell drawn as a `scale` x `scale` square.
+/// `delay` is the time between frames in hundredths of a second.
+/// Frames with more than 256 colors in total are drawn with the closest colors of the palette.
+pub fn encode_gif(frames: &[Frame], scale: usize, delay: u16) -> Vec<u8> {
+    let scale = scale.max(1);
+    let width = frames.first().map_or(1, |f| f.width() * scale);
+    let height = frames.first().map_or(1, |f| f.height() * scale);
+
+    let images: Vec<Vec<Color>> = frames.iter().map(|f| f.pixels(scale)).collect();
+    let palette = Palette::new(images.iter().flatten());
+
+    let mut gif = b"GIF89a".to_vec();
+    gif.extend(to_u16(width).to_le_bytes());
+    gif.extend(to_u16(height).to_le_bytes());
+    // Global color table with 2^(bits) entries, 8 bits per primary color.
+    gif.extend([0xf0 | (palette.bits - 1), 0, 0]);
+    for i in 0..1 << palette.bits {
+        let color = palette.colors.get(i).copied().unwrap_or_default();
+        gif.extend([color.r, color.g, color.b]);
+    }
+
+    // Loop the animation forever.
+    gif.extend([0x21, 0xff, 0x0b]);
+    gif.extend(b"NETSCAPE2.0");
+    gif.extend([0x03, 0x01, 0x00, 0x00, 0x00]);
+
+    for (frame, image) in frames.iter().zip(&images) {
+        // Frames of a different size are skipped, as they would not fit the screen.
+        if image.len() != width * height || frame.width() * scale != width {
+            continue;
+        }
+
+        gif.extend([0x21, 0xf9, 0x04, 0x00]);
+        gif.extend(delay.to_le_bytes());
+        gif.extend([0x00, 0x00]);
+
+        gif.extend([0x2c, 0, 0, 0, 0]);
+        gif.extend(to_u16(width).to_le_bytes());
+        gif.extend(to_u16(height).to_le_bytes());
+        gif.push(0);
+
+        let min_code_size = palette.bits.max(2);
+        let indices: Vec<u8> = image.iter().map(|c| palette.index(*c)).collect();
+        gif.push(min_code_size);
+        for block in lzw(&indices, min_code_size).chunks(255) {
+            #[allow(clippy::cast_possible_truncation)]
+            gif.push(block.len() as u8);
+            gif.extend(block);
+        }
+        gif.push(0);
+    }
+
+    gif.push(0x3b);
+    gif
+}
+
+struct Palette {
+    colors: Vec<Color>,
+    lookup: HashMap<Color, u8>,
+    /// Number of bits per color index, between 1 and 8.
+    bits: u8,
+}
+
+impl Palette {
+    fn new<'a>(pixels: impl Iterator<Item = &'a Color>) -> Self {
+        let mut colors: Vec<Color> = vec![];
+        let mut lookup = HashMap::new();
+
+        for color in pixels {
+            if colors.len() < 256 && !lookup.contains_key(color) {
+                #[allow(clippy::cast_possible_truncation)]
+                lookup.insert(*color, colors.len() as u8);
+                colors.push(*color);
+            }
+        }
+
+        let mut bits = 1;
+        while 1 << bits < colors.len() {
+            bits += 1;
+        }
+
+        Self {
+            colors,
+            lookup,
+            bits,
+        }
+    }
+
+    fn index(&self, color: Color) -> u8 {
+        if let Some(index) = self.lookup.get(&color) {
+            return *index;
+        }
+
+        let distance = |c: &Color| {
+            let d = |a: u8, b: u8| i32::from(a) - i32::from(b);
+            d(c.r, color.r).pow(2) + d(c.g, color.g).pow(2) + d(c.b, color.b).pow(2)
+        };
+        #[allow(clippy::cast_possible_truncation)]
+        self.colors
+            .iter()
+            .enumerate()
+            .min_by_key(|(_, c)| distance(c))
+            .map_or(0, |(i, _)| i as u8)
+    }
+}
+
+/// Compresses color indices with the variable-length LZW code of the GIF format.
+fn lzw(indices: &[u8], min_code_size: u8) -> Vec<u8> {
+    let clear: u16 = 1 << min_code_size;
+    let end = clear + 1;
+
+    let mut out = BitWriter::default();
+    let mut table: HashMap<(u16, u8), u16> = HashMap::new();
+    let mut next = end + 1;
+    let mut code_size = min_code_size + 1;
+
+    out.write(clear, code_size);
+
+    let Some((first, rest)) = indices.split_first() else {
+        out.write(end, code_size);
+        return out.finish();
+    };
+
+    let mut prefix = u16::from(*first);
+    for &index in rest {
+        if let Some(code) = table.get(&(prefix, index)) {
+            prefix = *code;
+            continue;
+        }
+
+        out.write(prefix, code_size);
+
+        if next <= MAX_CODE {
+            table.insert((prefix, index), next);
+            next += 1;
+            // The decoder adds its entries one code later, so it switches to wider codes here, too.
+            if next > 1 << code_size && code_size < 12 {
+                code_size += 1;
+            }
+        } else {
+            out.write(clear, code_size);
+            table.clear();
+            next = end + 1;
+            code_size = min_code_size + 1;
+        }
+
+        prefix = u16::from(index);
+    }
+
+    out.write(prefix, code_size);
+    out.write(end, code_size);
+    out.finish()
+}
+
+/// Packs codes least significant bit first, as GIF expects.
+#[derive(Default)]
+struct BitWriter {
+    bytes: Vec<u8>,
+    buffer: u32,
+    bits: u8,
+}
+
+impl BitWriter {
+    fn write(&mut self, code: u16, size: u8) {
+        self.buffer |= u32::from(code) << self.bits;
+        self.bits += size;
+        while self.bits >= 8 {
+            #[allow(clippy::cast_possible_truncation)]
+            self.bytes.push(self.buffer as u8);
+            self.buffer >>= 8;
+            self.bits -= 8;
+        }
+    }
+
+    fn finish(mut self) -> Vec<u8> {
+        if self.bits > 0 {
+            #[allow(clippy::cast_possible_truncation)]
+            self.bytes.push(self.buffer as u8);
+        }
+        self.bytes
+    }
+}
+
+fn to_u16(x: usize) -> u16 {
+    u16::try_from(x).expect("image is too large for a GIF")
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::{encode_gif, lzw};
+    use crate::viz::{Cell, Color, Frame};
+
+    /// Decodes a LZW stream, to check that the encoder agrees with the format.
+    fn decode(bytes: &[u8], min_code_size: u8) -> Vec<u8> {
+        let clear = 1usize << min_code_size;
+        let end = clear + 1;
+        let mut table: Vec<Vec<u8>> = vec![];
+        let mut code_size = min_code_size + 1;
+        let mut previous: Option<usize> = None;
+        let mut out = vec![];
+        let (mut buffer, mut bits, mut pos) = (0u32, 0u8, 0);
+
+        loop {
+            while bits < code_size {
+                buffer |= u32::from(bytes[pos]) << bits;
+                pos += 1;
+                bits += 8;
+            }
+            let code = (buffer & ((1 << code_size) - 1)) as usize;
+            buffer >>= code_size;
+            bits -= code_size;
+
+            if code == clear {
+                table = (0..clear).map(|i| vec![i as u8]).collect();
+                table.extend([vec![], vec![]]);
+                code_size = min_code_size + 1;
+                previous = None;
+                continue;
+            }
+            if code == end {
+                return out;
+            }
+
+            let entry = match (table.get(code), previous) {
+                (Some(entry), _) => entry.clone(),
+                (None, Some(p)) => {
+                    let mut entry = table[p].clone();
+                    entry.push(table[p][0]);
+                    entry
+                }
+                (None, None) => panic!("invalid code {code}"),
+            };
+            if let Some(p) = previous {
+                let mut new = table[p].clone();
+                new.push(entry[0]);
+                table.push(new);
+                if table.len() == 1 << code_size && code_size < 12 {
+                    code_size += 1;
+                }
+            }
+            out.extend(&entry);
+            previous = Some(code);
+        }
+    }
+
+    #[test]
+    fn roundtrips_lzw() {
+        let mut seed = 7u32;
+        let noise: Vec<u8> = (0..20_000)
+            .map(|_| {
+                seed = seed.wrapping_mul(1_103_515_245).wrapping_add(12345);
+                (seed >> 16) as u8 % 5
+            })
+            .collect();
+        let runs: Vec<u8> = (0..20_000).map(|i| (i / 300 % 2) as u8).collect();
+
+        for indices in [vec![], vec![1], noise, runs] {
+            assert_eq!(decode(&lzw(&indices, 3), 3), indices);
+        }
+    }
+
+    #[test]
+    fn encodes_animations() {
+        let frames: Vec<Frame> = (0..3)
+            .map(|i| {
+                Frame::from_sequence(&[i, i + 1], 2, |_, x| Cell::new('#', Color::indexed(*x)))
+            })
+            .collect();
+        let gif = encode_gif(&frames, 2, 5);
+
+        assert_eq!(&gif[..6], b"GIF89a");
+        assert_eq!(&gif[6..10], &[4, 0, 2, 0]);
+        assert!(gif.iter().filter(|x| **x == 0x2c).count() >= 3);
+        assert_eq!(gif.last(), Some(&0x3b));
+    }
+}
@@ -0,0 +1,447 @@
+//! Visualizations of grid and sequence states, e.g. a guard walking a grid or a disk being compacted.
+//!
+//! A [`Viz`] receives [`Frame`]s and shows them as an animation in the terminal, or writes them to
+//! PNG or GIF files. Recording only happens if the solution was built with the `viz` feature and
+//! started with `--viz`, which is what `cargo solve <day> --viz` does. Frames are built in a
+//! closure, so without the feature, the visualization code is optimized out of the solution.
+//!
+//! ```no_run
+//! use advent_of_code::grid::Grid;
+//! use advent_of_code::template::{Puzzle, Year, Day};
+//! use advent_of_code::viz::{Cell, Color, Frame, Viz};
+//!
+//! # let puzzle = Puzzle::new(Year::new(2024).unwrap(), Day::new(6).unwrap());
+//! let grid: Grid<char> = "#..\n.^.\n...".parse().unwrap();
+//! let mut viz = Viz::new(puzzle, "walk");
+//!
+//! viz.frame(|| {
+//!     Frame::from_grid(&grid, |_, c| match c {
+//!         '#' => Cell::new('#', Color::GRAY),
+//!         _ => Cell::new(*c, Color::YELLOW),
+//!     })
+//! });
+//! ```
+use std::{
+    fmt::Display,
+    fs,
+    io::{self, Write},
+    path::PathBuf,
+    str::FromStr,
+    thread,
+    time::Duration,
+};
+
+use crate::grid::{Grid, Point};
+use crate::template::{runner, Puzzle};
+
+mod gif;
+mod png;
+mod terminal;
+
+pub use gif::encode_gif;
+pub use png::encode_png;
+
+/// Whether the solution was built with the `viz` feature.
+pub const ENABLED: bool = cfg!(feature = "viz");
+
+pub const VIZ_ARG: &str = "--viz";
+
+/* -------------------------------------------------------------------------- */
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct Color {
+    pub r: u8,
+    pub g: u8,
+    pub b: u8,
+}
+
+impl Color {
+    pub const BLACK: Color = Color::rgb(0, 0, 0);
+    pub const WHITE: Color = Color::rgb(255, 255, 255);
+    pub const GRAY: Color = Color::rgb(110, 110, 120);
+    pub const RED: Color = Color::rgb(230, 70, 60);
+    pub const GREEN: Color = Color::rgb(80, 200, 90);
+    pub const BLUE: Color = Color::rgb(70, 130, 230);
+    pub const YELLOW: Color = Color::rgb(240, 200, 60);
+
+    pub const fn rgb(r: u8, g: u8, b: u8) -> Self {
+        Self { r, g, b }
+    }
+
+    /// Returns one of many distinct colors, e.g. to tell apart the files on a disk.
+    #[allow(
+        clippy::cast_possible_truncation,
+        clippy::cast_precision_loss,
+        clippy::cast_sign_loss
+    )]
+    pub fn indexed(index: usize) -> Self {
+        // Walking the hue by the golden ratio keeps neighboring indices far apart.
+        let hue = (index as f64 * 0.618_033_988_75).fract() * 6.0;
+        let x = 1.0 - (hue % 2.0 - 1.0).abs();
+        let (r, g, b) = match hue as u8 {
+            0 => (1.0, x, 0.0),
+            1 => (x, 1.0, 0.0),
+            2 => (0.0, 1.0, x),
+            3 => (0.0, x, 1.0),
+            4 => (x, 0.0, 1.0),
+            _ => (1.0, 0.0, x),
+        };
+        let channel = |c: f64| (60.0 + c * 180.0) as u8;
+        Self::rgb(channel(r), channel(g), channel(b))
+    }
+}
+
+/// A single cell of a frame. Images only show the color, the terminal also shows the character.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Cell {
+    pub ch: char,
+    pub color: Color,
+}
+
+impl Cell {
+    pub const EMPTY: Cell = Cell::new(' ', Color::BLACK);
+
+    pub const fn new(ch: char, color: Color) -> Self {
+        Self { ch, color }
+    }
+}
+
+/// A rectangle of cells with an optional caption, e.g. the step count.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Frame {
+    cells: Grid<Cell>,
+    caption: Option<String>,
+}
+
+impl Frame {
+    pub fn new(width: usize, height: usize) -> Self {
+        Self {
+            cells: Grid::new(width, height, Cell::EMPTY),
+            caption: None,
+        }
+    }
+
+    /// Draws every cell of a grid with `draw`.
+    pub fn from_grid<T>(grid: &Grid<T>, mut draw: impl FnMut(Point, &T) -> Cell) -> Self {
+        Self {
+            cells: Grid::from_vec(
+                grid.width(),
+                grid.iter()
+                    .map(|(point, value)| draw(point, value))
+                    .collect(),
+            ),
+            caption: None,
+        }
+    }
+
+    /// Draws a sequence, e.g. the blocks of a disk, in rows of `width` cells.
+    pub fn from_sequence<T>(
+        items: &[T],
+        width: usize,
+        mut draw: impl FnMut(usize, &T) -> Cell,
+    ) -> Self {
+        let width = width.max(1);
+        let mut cells: Vec<Cell> = items.iter().enumerate().map(|(i, x)| draw(i, x)).collect();
+        cells.resize(cells.len().div_ceil(width).max(1) * width, Cell::EMPTY);
+        Self {
+            cells: Grid::from_vec(width, cells),
+            caption: None,
+        }
+    }
+
+    #[must_use]
+    pub fn with_caption(mut self, caption: impl Display) -> Self {
+        self.caption = Some(caption.to_string());
+        self
+    }
+
+    pub fn width(&self) -> usize {
+        self.cells.width()
+    }
+
+    pub fn height(&self) -> usize {
+        self.cells.height()
+    }
+
+    pub fn caption(&self) -> Option<&str> {
+        self.caption.as_deref()
+    }
+
+    pub fn get(&self, point: Point) -> Option<&Cell> {
+        self.cells.get(point)
+    }
+
+    /// Overwrites a cell, e.g. to draw the guard on top of a grid. Points outside the frame are ignored.
+    pub fn set(&mut self, point: Point, cell: Cell) {
+        if let Some(x) = self.cells.get_mut(point) {
+            *x = cell;
+        }
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = &[Cell]> {
+        (0..self.height()).map(|y| self.cells.row(y))
+    }
+
+    /// Returns the RGB pixels of the frame, with every cell drawn as a `scale` x `scale` square.
+    pub fn pixels(&self, scale: usize) -> Vec<Color> {
+        let scale = scale.max(1);
+        let mut pixels = Vec::with_capacity(self.width() * self.height() * scale * scale);
+        for row in self.rows() {
+            for _ in 0..scale {
+                for cell in row {
+                    pixels.extend(std::iter::repeat_n(cell.color, scale));
+                }
+            }
+        }
+        pixels
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+/// Where the frames of a visualization go.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Target {
+    /// An ANSI-colored animation in the terminal.
+    Terminal,
+    /// One PNG file per frame.
+    Png,
+    /// A single animated GIF file.
+    Gif,
+}
+
+impl Display for Target {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Target::Terminal => f.write_str("terminal"),
+            Target::Png => f.write_str("png"),
+            Target::Gif => f.write_str("gif"),
+        }
+    }
+}
+
+impl FromStr for Target {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "terminal" => Ok(Target::Terminal),
+            "png" => Ok(Target::Png),
+            "gif" => Ok(Target::Gif),
+            s => Err(format!(
+                "unknown visualization target `{s}`, expected `terminal`, `png` or `gif`."
+            )),
+        }
+    }
+}
+
+/// Records the frames of a visualization.
+/// Files are written to `data/{year}/viz/{day}/`, prefixed with the name of the visualization.
+pub struct Viz {
+    target: Option<Target>,
+    dir: PathBuf,
+    name: String,
+    delay: Duration,
+    scale: usize,
+    every: usize,
+    steps: usize,
+    frames: Vec<Frame>,
+    written: usize,
+}
+
+impl Viz {
+    /// Creates a visualization that records frames if the solution was started with `--viz`.
+    pub fn new(puzzle: Puzzle, name: &str) -> Self {
+        Self {
+            target: if ENABLED { target_from_args() } else { None },
+            dir: puzzle
+                .year
+                .data_dir()
+                .join("viz")
+                .join(puzzle.day.to_string()),
+            name: name.to_string(),
+            delay: Duration::from_millis(50),
+            scale: 4,
+            every: 1,
+            steps: 0,
+            frames: vec![],
+            written: 0,
+        }
+    }
+
+    /// Sets the time between two frames. Defaults to 50ms.
+    #[must_use]
+    pub fn delay(mut self, delay: Duration) -> Self {
+        self.delay = delay;
+        self
+    }
+
+    /// Sets the size of a cell in pixels for image output. Defaults to 4.
+    #[must_use]
+    pub fn scale(mut self, scale: usize) -> Self {
+        self.scale = scale.max(1);
+        self
+    }
+
+    /// Only records every `n`th frame, for simulations with many steps.
+    #[must_use]
+    pub fn every(mut self, n: usize) -> Self {
+        self.every = n.max(1);
+        self
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        ENABLED && self.target.is_some()
+    }
+
+    /// Records a frame. `draw` is only called if the visualization is enabled.
+    pub fn frame(&mut self, draw: impl FnOnce() -> Frame) {
+        if !self.is_enabled() {
+            return;
+        }
+
+        self.steps += 1;
+        if !(self.steps - 1).is_multiple_of(self.every) {
+            return;
+        }
+
+        let frame = draw();
+        if let Err(e) = self.write(frame) {
+            eprintln!("could not write visualization: {e}");
+            self.target = None;
+        }
+    }
+
+    fn write(&mut self, frame: Frame) -> io::Result<()> {
+        match self.target {
+            Some(Target::Terminal) => {
+                let mut stdout = io::stdout().lock();
+                if self.written == 0 {
+                    terminal::start(&mut stdout)?;
+                }
+                terminal::draw(&mut stdout, &frame)?;
+                stdout.flush()?;
+                thread::sleep(self.delay);
+            }
+            Some(Target::Png) => {
+                fs::create_dir_all(&self.dir)?;
+                let path = self
+                    .dir
+                    .join(format!("{}-{:05}.png", self.name, self.written));
+                let pixels = frame.pixels(self.scale);
+                fs::write(
+                    path,
+                    encode_png(
+                        frame.width() * self.scale,
+                        frame.height() * self.scale,
+                        &pixels,
+                    ),
+                )?;
+            }
+            Some(Target::Gif) => self.frames.push(frame),
+            None => return Ok(()),
+        }
+        self.written += 1;
+        Ok(())
+    }
+
+    /// Finishes the visualization, e.g. writes the GIF file. Also happens when the `Viz` is dropped.
+    pub fn finish(&mut self) -> io::Result<()> {
+        if !self.is_enabled() || self.written == 0 {
+            return Ok(());
+        }
+
+        match self.target.take() {
+            Some(Target::Terminal) => terminal::finish(&mut io::stdout().lock())?,
+            Some(Target::Png) => eprintln!(
+                "Wrote {} frames to {}",
+                self.written,
+                self.dir.join(format!("{}-*.png", self.name)).display()
+            ),
+            Some(Target::Gif) => {
+                let frames = std::mem::take(&mut self.frames);
+                let delay = u16::try_from(self.delay.as_millis() / 10).unwrap_or(u16::MAX);
+                let gif = encode_gif(&frames, self.scale, delay);
+                fs::create_dir_all(&self.dir)?;
+                let path = self.dir.join(format!("{}.gif", self.name));
+                fs::write(&path, gif)?;
+                eprintln!("Wrote {} frames to {}", frames.len(), path.display());
+            }
+            None => {}
+        }
+        Ok(())
+    }
+}
+
+impl Drop for Viz {
+    fn drop(&mut self) {
+        if let Err(e) = self.finish() {
+            eprintln!("could not write visualization: {e}");
+        }
+    }
+}
+
+/// Reads the target from `--viz [terminal|png|gif]`. The target defaults to the terminal.
+fn target_from_args() -> Option<Target> {
+    let args = runner::args();
+    let index = args.iter().position(|x| x == VIZ_ARG)?;
+    match args.get(index + 1).filter(|x| !x.starts_with("--")) {
+        Some(target) => match target.parse() {
+            Ok(target) => Some(target),
+            Err(e) => {
+                eprintln!("{e}");
+                None
+            }
+        },
+        None => Some(Target::Terminal),
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::{Cell, Color, Frame, Target};
+    use crate::grid::{Grid, Point};
+
+    #[test]
+    fn draws_frames() {
+        let grid: Grid<char> = "#.\n.^".parse().unwrap();
+        let mut frame = Frame::from_grid(&grid, |_, c| match c {
+            '#' => Cell::new('#', Color::GRAY),
+            c => Cell::new(*c, Color::WHITE),
+        });
+        frame.set(Point::new(0, 1), Cell::new('X', Color::RED));
+        frame.set(Point::new(5, 5), Cell::new('X', Color::RED));
+
+        assert_eq!((frame.width(), frame.height()), (2, 2));
+        assert_eq!(frame.get(Point::new(0, 1)).unwrap().ch, 'X');
+
+        let pixels = frame.pixels(2);
+        assert_eq!(pixels.len(), 16);
+        assert_eq!(
+            &pixels[0..4],
+            &[Color::GRAY, Color::GRAY, Color::WHITE, Color::WHITE]
+        );
+        assert_eq!(pixels[8], Color::RED);
+    }
+
+    #[test]
+    fn draws_sequences() {
+        let disk = [Some(0), None, Some(1), Some(1), None];
+        let frame = Frame::from_sequence(&disk, 2, |_, block| match block {
+            Some(id) => Cell::new('#', Color::indexed(*id)),
+            None => Cell::EMPTY,
+        });
+        assert_eq!((frame.width(), frame.height()), (2, 3));
+        assert_eq!(frame.get(Point::new(1, 2)), Some(&Cell::EMPTY));
+        assert_ne!(Color::indexed(0), Color::indexed(1));
+    }
+
+    #[test]
+    fn parses_targets() {
+        assert_eq!("gif".parse(), Ok(Target::Gif));
+        assert_eq!("png".parse(), Ok(Target::Png));
+        assert!("mp4".parse::<Target>().is_err());
+    }
+}
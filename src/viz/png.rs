@@ -0,0 +1,125 @@
+//! A minimal PNG encoder. The image data is stored without compression, which keeps the encoder
+//! small. The files are larger than necessary, but still open in every viewer.
+use super::Color;
+
+const SIGNATURE: [u8; 8] = [0x89, b'P', b'N', b'G', b'\r', b'\n', 0x1a, b'\n'];
+
+/// Maximum length of a stored deflate block.
+const MAX_BLOCK: usize = 0xffff;
+
+/// Encodes `pixels` in row order as an 8-bit RGB PNG image.
+pub fn encode_png(width: usize, height: usize, pixels: &[Color]) -> Vec<u8> {
+    assert_eq!(
+        pixels.len(),
+        width * height,
+        "pixels do not match the image size"
+    );
+
+    let mut raw = Vec::with_capacity(height * (width * 3 + 1));
+    for row in pixels.chunks(width.max(1)) {
+        // Every row starts with its filter type, 0 means no filter.
+        raw.push(0);
+        for pixel in row {
+            raw.extend([pixel.r, pixel.g, pixel.b]);
+        }
+    }
+
+    let mut header = Vec::with_capacity(13);
+    header.extend(to_u32(width).to_be_bytes());
+    header.extend(to_u32(height).to_be_bytes());
+    // Bit depth 8, color type RGB, default compression, filter and interlace methods.
+    header.extend([8, 2, 0, 0, 0]);
+
+    let mut png = SIGNATURE.to_vec();
+    write_chunk(&mut png, b"IHDR", &header);
+    write_chunk(&mut png, b"IDAT", &zlib_stored(&raw));
+    write_chunk(&mut png, b"IEND", &[]);
+    png
+}
+
+fn write_chunk(png: &mut Vec<u8>, kind: &[u8; 4], data: &[u8]) {
+    png.extend(to_u32(data.len()).to_be_bytes());
+    let start = png.len();
+    png.extend(kind);
+    png.extend(data);
+    let crc = crc32(&png[start..]);
+    png.extend(crc.to_be_bytes());
+}
+
+/// Wraps `data` in a zlib stream of uncompressed deflate blocks.
+fn zlib_stored(data: &[u8]) -> Vec<u8> {
+    let mut out = Vec::with_capacity(data.len() + data.len() / MAX_BLOCK * 5 + 11);
+    // Deflate with a 32K window, no preset dictionary. The check bits make the header divisible by 31.
+    out.extend([0x78, 0x01]);
+
+    let mut blocks = data.chunks(MAX_BLOCK).peekable();
+    if blocks.peek().is_none() {
+        out.extend([1, 0, 0, 0xff, 0xff]);
+    }
+    while let Some(block) = blocks.next() {
+        let is_last = blocks.peek().is_none();
+        #[allow(clippy::cast_possible_truncation)]
+        let len = block.len() as u16;
+        out.push(u8::from(is_last));
+        out.extend(len.to_le_bytes());
+        out.extend((!len).to_le_bytes());
+        out.extend(block);
+    }
+
+    out.extend(adler32(data).to_be_bytes());
+    out
+}
+
+fn crc32(data: &[u8]) -> u32 {
+    let mut crc = !0u32;
+    for byte in data {
+        crc ^= u32::from(*byte);
+        for _ in 0..8 {
+            crc = if crc & 1 == 1 {
+                (crc >> 1) ^ 0xedb8_8320
+            } else {
+                crc >> 1
+            };
+        }
+    }
+    !crc
+}
+
+fn adler32(data: &[u8]) -> u32 {
+    let (mut a, mut b) = (1u32, 0u32);
+    for byte in data {
+        a = (a + u32::from(*byte)) % 65521;
+        b = (b + a) % 65521;
+    }
+    (b << 16) | a
+}
+
+fn to_u32(x: usize) -> u32 {
+    u32::try_from(x).expect("image is too large")
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::{adler32, crc32, encode_png};
+    use crate::viz::Color;
+
+    #[test]
+    fn computes_checksums() {
+        assert_eq!(crc32(b"IEND"), 0xae42_6082);
+        assert_eq!(adler32(b"Wikipedia"), 0x11e6_0398);
+    }
+
+    #[test]
+    fn encodes_images() {
+        let png = encode_png(2, 1, &[Color::RED, Color::BLUE]);
+        assert_eq!(&png[..8], b"\x89PNG\r\n\x1a\n");
+        assert_eq!(&png[12..16], b"IHDR");
+        assert_eq!(&png[16..24], &[0, 0, 0, 2, 0, 0, 0, 1]);
+        // The image data is one filter byte followed by two RGB pixels.
+        let idat = png.windows(4).position(|w| w == b"IDAT").unwrap();
+        assert_eq!(&png[idat + 11..idat + 18], &[0, 230, 70, 60, 70, 130, 230]);
+        assert_eq!(&png[png.len() - 12..], b"\0\0\0\0IEND\xae\x42\x60\x82");
+    }
+}
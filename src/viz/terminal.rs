@@ -0,0 +1,46 @@
+//! Draws frames as an ANSI-colored animation. Every frame overwrites the previous one.
+use std::io::{self, Write};
+
+use super::Frame;
+use crate::template::ANSI_RESET;
+
+const CLEAR_SCREEN: &str = "\x1b[2J";
+const CURSOR_HOME: &str = "\x1b[H";
+const CLEAR_LINE: &str = "\x1b[K";
+const HIDE_CURSOR: &str = "\x1b[?25l";
+const SHOW_CURSOR: &str = "\x1b[?25h";
+
+pub fn start(out: &mut impl Write) -> io::Result<()> {
+    write!(out, "{CLEAR_SCREEN}{HIDE_CURSOR}")
+}
+
+pub fn draw(out: &mut impl Write, frame: &Frame) -> io::Result<()> {
+    let mut buf = String::from(CURSOR_HOME);
+
+    for row in frame.rows() {
+        let mut color = None;
+        for cell in row {
+            if color != Some(cell.color) {
+                let c = cell.color;
+                buf.push_str(&format!("\x1b[38;2;{};{};{}m", c.r, c.g, c.b));
+                color = Some(c);
+            }
+            buf.push(cell.ch);
+        }
+        buf.push_str(ANSI_RESET);
+        buf.push('\n');
+    }
+
+    if let Some(caption) = frame.caption() {
+        buf.push_str(caption);
+        buf.push_str(CLEAR_LINE);
+        buf.push('\n');
+    }
+
+    out.write_all(buf.as_bytes())
+}
+
+pub fn finish(out: &mut impl Write) -> io::Result<()> {
+    write!(out, "{SHOW_CURSOR}")?;
+    out.flush()
+}